@@ -0,0 +1,117 @@
+/// A color with red, green and blue channels.
+pub type Rgb = [u8; 3];
+
+/// A color ramp to map intensities to colors.
+///
+/// The stops are spread evenly over the range `0.0..=1.0`,
+/// values between two stops are interpolated linearly.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColorRamp {
+    stops: Vec<Rgb>,
+}
+
+impl ColorRamp {
+    /// Create a new color ramp from the given stops.
+    ///
+    /// Panics if no stops are given.
+    pub fn new(stops: Vec<Rgb>) -> ColorRamp {
+        assert!(!stops.is_empty(), "A color ramp needs at least one stop");
+        ColorRamp { stops }
+    }
+
+    /// A ramp from black over red and yellow to white.
+    pub fn heat() -> ColorRamp {
+        ColorRamp::new(vec![[0, 0, 0], [255, 0, 0], [255, 255, 0], [255, 255, 255]])
+    }
+
+    /// Determine the color for the given intensity.
+    ///
+    /// The intensity is clamped to `0.0..=1.0`.
+    pub fn color(&self, intensity: f64) -> Rgb {
+        if self.stops.len() == 1 {
+            return self.stops[0];
+        }
+
+        let position = intensity.clamp(0.0, 1.0) * (self.stops.len() - 1) as f64;
+        let index = (position.floor() as usize).min(self.stops.len() - 2);
+        let fraction = position - index as f64;
+
+        let from = self.stops[index];
+        let to = self.stops[index + 1];
+        let mut color = [0u8; 3];
+
+        for channel in 0..3 {
+            let value =
+                from[channel] as f64 + (to[channel] as f64 - from[channel] as f64) * fraction;
+            color[channel] = value.round() as u8;
+        }
+
+        color
+    }
+}
+
+/// Encode a grayscale image as binary PGM (`P5`).
+///
+/// The pixels are given row by row.
+pub fn encode_pgm(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Pixel count does not match the size"
+    );
+
+    let mut output = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+    output.extend_from_slice(pixels);
+    output
+}
+
+/// Encode a color image as binary PPM (`P6`).
+///
+/// The pixels are given row by row.
+pub fn encode_ppm(width: usize, height: usize, pixels: &[Rgb]) -> Vec<u8> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Pixel count does not match the size"
+    );
+
+    let mut output = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+    for pixel in pixels {
+        output.extend_from_slice(pixel);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::{encode_pgm, encode_ppm, ColorRamp};
+
+    #[test]
+    fn should_interpolate_color_ramp() {
+        let ramp = ColorRamp::new(vec![[0, 0, 0], [200, 100, 0], [200, 200, 200]]);
+
+        assert_eq!(ramp.color(0.0), [0, 0, 0]);
+        assert_eq!(ramp.color(0.25), [100, 50, 0]);
+        assert_eq!(ramp.color(0.5), [200, 100, 0]);
+        assert_eq!(ramp.color(1.0), [200, 200, 200]);
+        assert_eq!(ramp.color(2.0), [200, 200, 200]);
+    }
+
+    #[test]
+    fn should_encode_pgm() {
+        let expected = b"P5\n2 1\n255\n\x00\xff".to_vec();
+        let actual = encode_pgm(2, 1, &[0, 255]);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_encode_ppm() {
+        let expected = b"P6\n1 2\n255\n\x01\x02\x03\x04\x05\x06".to_vec();
+        let actual = encode_ppm(1, 2, &[[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(actual, expected);
+    }
+}
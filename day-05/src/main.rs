@@ -1,16 +1,20 @@
-use std::{fs, thread};
+use std::{env, fs};
+
+use image::{encode_pgm, encode_ppm, ColorRamp};
+
+mod image;
 
 #[derive(Debug, PartialEq)]
 struct ParseError(String);
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 struct Point {
-    x: usize,
-    y: usize,
+    x: i64,
+    y: i64,
 }
 
 impl Point {
-    fn new(x: usize, y: usize) -> Point {
+    fn new(x: i64, y: i64) -> Point {
         Point { x, y }
     }
 
@@ -20,10 +24,10 @@ impl Point {
     ///
     /// `3,4`
     fn from_input(input: String) -> Result<Point, ParseError> {
-        let mut parts = input.split(",").into_iter();
+        let mut parts = input.split(",");
 
         // Parse x
-        let x: usize = if let Some(x_part) = parts.next() {
+        let x: i64 = if let Some(x_part) = parts.next() {
             if let Ok(x) = x_part.parse() {
                 x
             } else {
@@ -34,7 +38,7 @@ impl Point {
         };
 
         // Parse y
-        let y: usize = if let Some(y_part) = parts.next() {
+        let y: i64 = if let Some(y_part) = parts.next() {
             if let Ok(x) = y_part.parse() {
                 x
             } else {
@@ -70,7 +74,7 @@ impl Line {
     ///
     /// `3,4 -> 1,4`
     fn from_input(input: String) -> Result<Line, ParseError> {
-        let mut parts = input.split(" -> ").into_iter();
+        let mut parts = input.split(" -> ");
 
         // Parse start
         let start: Point = if let Some(start_part) = parts.next() {
@@ -94,25 +98,35 @@ impl Line {
             return Err(ParseError(input));
         };
 
-        Ok(Line { start, end })
+        Ok(Line::new(start, end))
     }
 
-    fn max_x(&self) -> usize {
+    fn max_x(&self) -> i64 {
         self.start.x.max(self.end.x)
     }
 
-    fn max_y(&self) -> usize {
+    fn max_y(&self) -> i64 {
         self.start.y.max(self.end.y)
     }
 
-    fn min_x(&self) -> usize {
+    fn min_x(&self) -> i64 {
         self.start.x.min(self.end.x)
     }
 
-    fn min_y(&self) -> usize {
+    fn min_y(&self) -> i64 {
         self.start.y.min(self.end.y)
     }
 
+    /// The point with the lowest coordinates covered by the line.
+    fn top_left(&self) -> Point {
+        Point::new(self.min_x(), self.min_y())
+    }
+
+    /// The point with the highest coordinates covered by the line.
+    fn bottom_right(&self) -> Point {
+        Point::new(self.max_x(), self.max_y())
+    }
+
     /// Determines if the line is horizontal.
     fn is_horizontal(&self) -> bool {
         self.start.y == self.end.y
@@ -128,6 +142,11 @@ impl Line {
         (self.max_x() - self.min_x()) == (self.max_y() - self.min_y())
     }
 
+    /// Determines if the line is horizontal, vertical or diagonal, so it can be drawn.
+    fn is_drawable(&self) -> bool {
+        self.is_horizontal() || self.is_vertical() || self.is_diagonal()
+    }
+
     /// Get the points on the given line.
     ///
     /// Currently only works for horizontal, vertical and diagonal lines.
@@ -143,21 +162,11 @@ impl Line {
                 points.push(Point::new(self.start.x, y));
             }
         } else if self.is_diagonal() {
+            let dx = (self.end.x - self.start.x).signum();
+            let dy = (self.end.y - self.start.y).signum();
+
             for d in 0..(self.max_x() - self.min_x() + 1) {
-                let dx: isize = if self.start.x <= self.end.x {
-                    d as isize
-                } else {
-                    -(d as isize)
-                };
-                let dy: isize = if self.start.y <= self.end.y {
-                    d as isize
-                } else {
-                    -(d as isize)
-                };
-                points.push(Point::new(
-                    ((self.start.x as isize) + dx) as usize,
-                    ((self.start.y as isize) + dy) as usize,
-                ));
+                points.push(Point::new(self.start.x + d * dx, self.start.y + d * dy));
             }
         }
 
@@ -170,26 +179,166 @@ impl Line {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Diagram<const R: usize, const C: usize> {
-    values: [[usize; R]; C],
+#[derive(Debug, PartialEq, Clone)]
+struct Diagram {
+    /// The point with the lowest coordinates covered by the diagram.
+    origin: Point,
+    width: usize,
+    height: usize,
+    /// The values row by row, relative to the origin.
+    values: Vec<usize>,
 }
 
-impl<const R: usize, const C: usize> Diagram<R, C> {
+impl Diagram {
     /// Create a new, empty diagram.
-    fn new() -> Diagram<R, C> {
+    ///
+    /// The diagram grows as points are added to it.
+    fn new() -> Diagram {
         Diagram {
-            values: [[0usize; R]; C],
+            origin: Point::new(0, 0),
+            width: 0,
+            height: 0,
+            values: vec![],
+        }
+    }
+
+    /// Create a new, empty diagram covering the rectangle between the two corners.
+    ///
+    /// Both corners are inclusive.
+    fn with_bounds(top_left: Point, bottom_right: Point) -> Diagram {
+        let mut diagram = Diagram::new();
+        diagram.grow(top_left, bottom_right);
+        diagram
+    }
+
+    /// Create a new diagram with all lines drawn in.
+    ///
+    /// The bounds of all lines are determined first, so the diagram is only allocated once.
+    /// Lines that cannot be drawn don't count towards the bounds.
+    fn from_lines(lines: &[Line]) -> Diagram {
+        let bounds = lines
+            .iter()
+            .filter(|line| line.is_drawable())
+            .map(|line| (line.top_left(), line.bottom_right()))
+            .reduce(
+                |(top_left, bottom_right), (line_top_left, line_bottom_right)| {
+                    (
+                        Point::new(
+                            top_left.x.min(line_top_left.x),
+                            top_left.y.min(line_top_left.y),
+                        ),
+                        Point::new(
+                            bottom_right.x.max(line_bottom_right.x),
+                            bottom_right.y.max(line_bottom_right.y),
+                        ),
+                    )
+                },
+            );
+
+        let mut diagram = match bounds {
+            Some((top_left, bottom_right)) => Diagram::with_bounds(top_left, bottom_right),
+            None => Diagram::new(),
+        };
+
+        for &line in lines {
+            diagram.add_line(line);
+        }
+
+        diagram
+    }
+
+    /// The point with the lowest coordinates covered by the diagram.
+    fn top_left(&self) -> Point {
+        self.origin
+    }
+
+    /// The point with the highest coordinates covered by the diagram.
+    fn bottom_right(&self) -> Point {
+        Point::new(
+            self.origin.x + self.width as i64 - 1,
+            self.origin.y + self.height as i64 - 1,
+        )
+    }
+
+    /// Determines if the diagram does not cover any points yet.
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The index of the point in the backing store, if it is covered by the diagram.
+    fn index(&self, point: Point) -> Option<usize> {
+        let col = point.x - self.origin.x;
+        let row = point.y - self.origin.y;
+
+        if col >= 0 && row >= 0 && (col as usize) < self.width && (row as usize) < self.height {
+            Some(row as usize * self.width + col as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Grow the diagram so that it also covers the rectangle between the two corners.
+    fn grow(&mut self, top_left: Point, bottom_right: Point) {
+        let (top_left, bottom_right) = if self.is_empty() {
+            (top_left, bottom_right)
+        } else if self.index(top_left).is_some() && self.index(bottom_right).is_some() {
+            // Already covered
+            return;
+        } else {
+            let cur_top_left = self.top_left();
+            let cur_bottom_right = self.bottom_right();
+
+            (
+                Point::new(
+                    top_left.x.min(cur_top_left.x),
+                    top_left.y.min(cur_top_left.y),
+                ),
+                Point::new(
+                    bottom_right.x.max(cur_bottom_right.x),
+                    bottom_right.y.max(cur_bottom_right.y),
+                ),
+            )
+        };
+
+        let width = (bottom_right.x - top_left.x + 1) as usize;
+        let height = (bottom_right.y - top_left.y + 1) as usize;
+        let mut grown = Diagram {
+            origin: top_left,
+            width,
+            height,
+            values: vec![0; width * height],
+        };
+
+        // Copy over the existing values
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let point = Point::new(self.origin.x + col as i64, self.origin.y + row as i64);
+                let index = grown.index(point).unwrap();
+                grown.values[index] = self.values[row * self.width + col];
+            }
         }
+
+        *self = grown;
     }
 
     /// Add a point to the diagram.
     fn add_point(&mut self, point: Point) {
-        self.values[point.y][point.x] += 1;
+        self.grow(point, point);
+        let index = self.index(point).unwrap();
+        self.values[index] += 1;
     }
 
     /// Add a line to the diagram.
+    ///
+    /// Lines that are neither horizontal, vertical nor diagonal are skipped.
     fn add_line(&mut self, line: Line) {
+        if !line.is_drawable() {
+            return;
+        }
+
+        // Grow once for the whole line instead of for every point
+        self.grow(line.top_left(), line.bottom_right());
+
         for point in line.points() {
             self.add_point(point);
         }
@@ -199,32 +348,47 @@ impl<const R: usize, const C: usize> Diagram<R, C> {
     ///
     /// This means lines overlap at that many points.
     fn count_points(&self, threshold: usize) -> usize {
-        let mut count = 0;
+        self.values
+            .iter()
+            .filter(|&&value| value >= threshold)
+            .count()
+    }
 
-        for row in 0..R {
-            for col in 0..C {
-                if self.values[row][col] >= threshold {
-                    count += 1;
-                }
-            }
+    /// The value of the diagram at the given point.
+    ///
+    /// Points outside of the diagram have a value of zero.
+    fn value(&self, point: Point) -> usize {
+        match self.index(point) {
+            Some(index) => self.values[index],
+            None => 0,
         }
+    }
 
-        count
+    /// The highest value in the diagram.
+    fn max_value(&self) -> usize {
+        self.values.iter().copied().max().unwrap_or(0)
     }
 
-    /// Create a string from the diagram.
-    fn stringify(&self) -> String {
-        let mut output = "".to_string();
+    /// The character representing the value of a single point.
+    ///
+    /// Values above 9 are shown as `#` to keep the columns aligned.
+    fn value_char(value: usize) -> char {
+        match value {
+            0 => '.',
+            1..=9 => char::from_digit(value as u32, 10).unwrap(),
+            _ => '#',
+        }
+    }
 
-        for row in 0..R {
-            for col in 0..C {
-                let value = self.values[row][col];
+    /// Create a string from the rectangle between the two corners.
+    ///
+    /// Both corners are inclusive.
+    fn stringify_viewport(&self, top_left: Point, bottom_right: Point) -> String {
+        let mut output = "".to_string();
 
-                if value == 0 {
-                    output += ".";
-                } else {
-                    output += &value.to_string();
-                }
+        for y in top_left.y..(bottom_right.y + 1) {
+            for x in top_left.x..(bottom_right.x + 1) {
+                output.push(Self::value_char(self.value(Point::new(x, y))));
             }
 
             output += "\n";
@@ -232,60 +396,93 @@ impl<const R: usize, const C: usize> Diagram<R, C> {
 
         output
     }
-}
 
-const STACK_SIZE: usize = 16 * 1024 * 1024;
+    /// The intensity of each point, scaled to the highest value.
+    fn intensities(&self) -> Vec<f64> {
+        let max_value = self.max_value().max(1) as f64;
 
-fn main() {
-    // Run the code in a new thread to prevent stack overflows
-    let child = thread::Builder::new()
-        .stack_size(STACK_SIZE)
-        .spawn(run)
-        .unwrap();
-
-    // Wait for thread to join
-    child.join().unwrap();
+        self.values
+            .iter()
+            .map(|&value| value as f64 / max_value)
+            .collect()
+    }
+
+    /// Export the diagram as grayscale PGM image.
+    fn export_pgm(&self) -> Vec<u8> {
+        let pixels: Vec<u8> = self
+            .intensities()
+            .into_iter()
+            .map(|intensity| (intensity * 255.0).round() as u8)
+            .collect();
+
+        encode_pgm(self.width, self.height, &pixels)
+    }
+
+    /// Export the diagram as PPM heatmap, colored with the given ramp.
+    fn export_ppm(&self, ramp: &ColorRamp) -> Vec<u8> {
+        let pixels: Vec<[u8; 3]> = self
+            .intensities()
+            .into_iter()
+            .map(|intensity| ramp.color(intensity))
+            .collect();
+
+        encode_ppm(self.width, self.height, &pixels)
+    }
 }
 
-fn run() {
+fn main() {
     // Read the input file
-    println!("Starting");
     let filename = "./input/input.txt";
     let input = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
-    let lines = parse_input(input);
-    println!("Creating diagram");
-    let mut diagram = Box::new(Diagram::<1000, 1000>::new());
-    println!("Diagram created");
-
     // Draw in all horizontal or vertical lines
-    for line in lines {
-        diagram.add_line(line);
-    }
+    let lines = parse_input(input);
+    let diagram = Diagram::from_lines(&lines);
 
     let count = diagram.count_points(2);
     println!("At least two lines overlap at {} points!", count);
+
+    // Optionally export the diagram as heatmap
+    if let Some(path) = env::args().nth(1) {
+        let image = if path.ends_with(".pgm") {
+            diagram.export_pgm()
+        } else {
+            diagram.export_ppm(&ColorRamp::heat())
+        };
+
+        fs::write(&path, image).expect("Something went wrong writing the heatmap");
+        println!("Heatmap written to {}", path);
+    }
+
+    // Optionally print a viewport of the diagram, given by its corners like a line
+    if let Some(viewport) = env::args().nth(2) {
+        let viewport =
+            Line::from_input(viewport).expect("Something went wrong parsing the viewport");
+
+        println!("Viewport {}:", viewport.stringify());
+        print!(
+            "{}",
+            diagram.stringify_viewport(viewport.top_left(), viewport.bottom_right())
+        );
+    }
 }
 
 fn parse_input(input: String) -> Vec<Line> {
-    let line_strs = input.split("\n").into_iter();
+    let line_strs = input.split("\n");
 
     line_strs
         // Parse the line
         .map(|line_str| Line::from_input(line_str.to_string()))
         // Filter out invalid lines
-        .filter(|res| match res {
-            Ok(_) => true,
-            Err(_) => false,
-        })
-        // Extract the result
-        .map(|res| res.unwrap())
+        .filter_map(|res| res.ok())
         .collect()
 }
 
 #[cfg(test)]
+// The line classification tests compare with literal booleans
+#[allow(clippy::bool_assert_comparison)]
 mod test {
-    use crate::{parse_input, Diagram, Line, Point};
+    use crate::{parse_input, ColorRamp, Diagram, Line, Point};
 
     #[test]
     fn should_parse_point_from_input() {
@@ -305,6 +502,15 @@ mod test {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn should_parse_negative_point_from_input() {
+        let input = "-3,-14".to_string();
+        let expected = Ok(Point::new(-3, -14));
+        let actual = Point::from_input(input);
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn should_determine_horizontal_line_true() {
         let line = Line::new(Point::new(2, 4), Point::new(9, 4));
@@ -380,14 +586,26 @@ mod test {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn should_determine_points_on_diagonal_line_through_origin() {
+        let line = Line::new(Point::new(1, -1), Point::new(-1, 1));
+        let expected = vec![Point::new(1, -1), Point::new(0, 0), Point::new(-1, 1)];
+        let actual = line.points();
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn should_add_point_to_diagram() {
         let point = Point::new(1, 2);
         let expected = Diagram {
-            values: [[0, 0, 0], [0, 0, 0], [0, 1, 0]],
+            origin: Point::new(0, 0),
+            width: 3,
+            height: 3,
+            values: vec![0, 0, 0, 0, 0, 0, 0, 1, 0],
         };
 
-        let mut actual = Diagram::<3, 3>::new();
+        let mut actual = Diagram::with_bounds(Point::new(0, 0), Point::new(2, 2));
         actual.add_point(point);
 
         assert_eq!(actual, expected);
@@ -397,20 +615,78 @@ mod test {
     fn should_add_line_to_diagram() {
         let line = Line::new(Point::new(1, 2), Point::new(1, 0));
         let expected = Diagram {
-            values: [[0, 1, 0], [0, 1, 0], [0, 1, 0]],
+            origin: Point::new(0, 0),
+            width: 3,
+            height: 3,
+            values: vec![0, 1, 0, 0, 1, 0, 0, 1, 0],
         };
 
-        let mut actual = Diagram::<3, 3>::new();
+        let mut actual = Diagram::with_bounds(Point::new(0, 0), Point::new(2, 2));
         actual.add_line(line);
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn should_grow_diagram_to_negative_points() {
+        let mut diagram = Diagram::new();
+        diagram.add_line(Line::new(Point::new(0, 0), Point::new(1, 0)));
+        diagram.add_line(Line::new(Point::new(-1, -1), Point::new(-1, 1)));
+
+        let expected = Diagram {
+            origin: Point::new(-1, -1),
+            width: 3,
+            height: 3,
+            values: vec![1, 0, 0, 1, 1, 1, 1, 0, 0],
+        };
+
+        assert_eq!(diagram, expected);
+        assert_eq!(diagram.value(Point::new(-1, 0)), 1);
+        assert_eq!(diagram.value(Point::new(-2, 0)), 0);
+    }
+
+    #[test]
+    fn should_create_diagram_from_lines() {
+        let lines = vec![
+            Line::new(Point::new(0, 0), Point::new(1, 0)),
+            Line::new(Point::new(-1, -1), Point::new(-1, 1)),
+        ];
+        let mut expected = Diagram::new();
+
+        for &line in &lines {
+            expected.add_line(line);
+        }
+
+        let actual = Diagram::from_lines(&lines);
+
+        assert_eq!(actual, expected);
+        assert_eq!(Diagram::from_lines(&[]), Diagram::new());
+    }
+
+    #[test]
+    fn should_not_grow_diagram_for_skipped_lines() {
+        let skipped = Line::new(Point::new(0, 0), Point::new(100000, 100001));
+        let line = Line::new(Point::new(0, 0), Point::new(1, 0));
+        let mut diagram = Diagram::new();
+        diagram.add_line(skipped);
+
+        assert_eq!(diagram, Diagram::new());
+        assert_eq!(Diagram::from_lines(&[skipped]), Diagram::new());
+        assert_eq!(
+            Diagram::from_lines(&[skipped, line]),
+            Diagram::from_lines(&[line])
+        );
+        assert_eq!(
+            Diagram::from_lines(&[skipped, line]).bottom_right(),
+            Point::new(1, 0)
+        );
+    }
+
     #[test]
     fn should_count_diagram_points() {
         let line1 = Line::new(Point::new(1, 2), Point::new(1, 0));
         let line2 = Line::new(Point::new(1, 1), Point::new(2, 1));
-        let mut diagram = Diagram::<3, 3>::new();
+        let mut diagram = Diagram::with_bounds(Point::new(0, 0), Point::new(2, 2));
         diagram.add_line(line1);
         diagram.add_line(line2);
 
@@ -430,4 +706,54 @@ mod test {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn should_stringify_diagram() {
+        let mut diagram = Diagram::with_bounds(Point::new(0, 0), Point::new(2, 2));
+        diagram.add_line(Line::new(Point::new(0, 0), Point::new(2, 2)));
+
+        for _ in 0..10 {
+            diagram.add_point(Point::new(2, 0));
+        }
+
+        let expected = "1.#\n.1.\n..1\n";
+        let actual = diagram.stringify_viewport(diagram.top_left(), diagram.bottom_right());
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_stringify_diagram_viewport() {
+        let mut diagram = Diagram::with_bounds(Point::new(0, 0), Point::new(2, 2));
+        diagram.add_line(Line::new(Point::new(0, 0), Point::new(2, 2)));
+
+        let expected = "1.\n.1\n..\n";
+        let actual = diagram.stringify_viewport(Point::new(1, 1), Point::new(2, 3));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_export_diagram_as_pgm() {
+        let mut diagram = Diagram::with_bounds(Point::new(0, 0), Point::new(1, 1));
+        diagram.add_line(Line::new(Point::new(0, 0), Point::new(1, 0)));
+        diagram.add_point(Point::new(1, 0));
+
+        let expected = b"P5\n2 2\n255\n\x80\xff\x00\x00".to_vec();
+        let actual = diagram.export_pgm();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_export_diagram_as_ppm() {
+        let mut diagram = Diagram::with_bounds(Point::new(0, 0), Point::new(1, 0));
+        diagram.add_point(Point::new(1, 0));
+        let ramp = ColorRamp::new(vec![[0, 0, 255], [255, 0, 0]]);
+
+        let expected = b"P6\n2 1\n255\n\x00\x00\xff\xff\x00\x00".to_vec();
+        let actual = diagram.export_ppm(&ramp);
+
+        assert_eq!(actual, expected);
+    }
 }
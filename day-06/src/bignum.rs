@@ -0,0 +1,196 @@
+use std::fmt;
+
+/// An unsigned integer of arbitrary size.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BigUint {
+    /// The 32 bit limbs, least significant first and without trailing zeros.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint { limbs: vec![] }
+    }
+
+    pub fn from_u128(mut value: u128) -> BigUint {
+        let mut limbs = vec![];
+
+        while value > 0 {
+            limbs.push(value as u32);
+            value >>= 32;
+        }
+
+        BigUint { limbs }
+    }
+
+    /// Convert the number to `u128`, if it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |value, &limb| (value << 32) | limb as u128),
+        )
+    }
+
+    /// Convert the number to a float, losing precision for big numbers.
+    pub fn to_f64(&self) -> f64 {
+        self.limbs
+            .iter()
+            .rev()
+            .fold(0.0, |value, &limb| value * 4294967296.0 + limb as f64)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Remove the leading zero limbs.
+    fn normalize(mut self) -> BigUint {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+
+        self
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+
+        for i in 0..len {
+            let a = *self.limbs.get(i).unwrap_or(&0) as u64;
+            let b = *other.limbs.get(i).unwrap_or(&0) as u64;
+            let sum = a + b + carry;
+
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+
+        limbs.push(carry as u32);
+        BigUint { limbs }.normalize()
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.normalize()
+    }
+
+    /// Divide by a small divisor, returning the quotient and the remainder.
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut limbs = vec![0u32; self.limbs.len()];
+        let mut remainder = 0u64;
+
+        for i in (0..self.limbs.len()).rev() {
+            let value = (remainder << 32) | self.limbs[i] as u64;
+
+            limbs[i] = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+
+        (BigUint { limbs }.normalize(), remainder as u32)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Split into chunks of nine decimal digits, least significant first
+        let mut chunks = vec![];
+        let mut rest = self.clone();
+
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_small(1_000_000_000);
+
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+
+        for chunk in chunks.into_iter().rev() {
+            write!(f, "{:09}", chunk)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bignum::BigUint;
+
+    #[test]
+    fn should_convert_from_and_to_u128() {
+        for value in [0, 1, u32::MAX as u128 + 1, u128::MAX] {
+            assert_eq!(BigUint::from_u128(value).to_u128(), Some(value));
+        }
+    }
+
+    #[test]
+    fn should_convert_to_f64() {
+        let value = BigUint::from_u128(u128::MAX).mul(&BigUint::from_u128(1 << 100));
+
+        assert_eq!(BigUint::zero().to_f64(), 0.0);
+        assert_eq!(BigUint::from_u128(26984457539).to_f64(), 26984457539.0);
+        assert_eq!(value.to_f64(), 2f64.powi(228));
+    }
+
+    #[test]
+    fn should_add_numbers() {
+        let a = BigUint::from_u128(u128::MAX);
+        let b = BigUint::from_u128(1);
+        let actual = a.add(&b);
+
+        assert_eq!(actual.to_u128(), None);
+        assert_eq!(
+            actual.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+    }
+
+    #[test]
+    fn should_multiply_numbers() {
+        let a = BigUint::from_u128(u128::MAX);
+        let actual = a.mul(&a);
+
+        assert_eq!(
+            actual.to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(a.mul(&BigUint::zero()), BigUint::zero());
+    }
+
+    #[test]
+    fn should_format_numbers() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from_u128(1_000_000_007).to_string(), "1000000007");
+        assert_eq!(BigUint::from_u128(26984457539).to_string(), "26984457539");
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::{Debug, Display};
+
+use crate::bignum::BigUint;
+
+/// A number type to count fishes with.
+pub trait FishCount: Clone + Debug + Display + PartialEq {
+    fn from_u128(value: u128) -> Self;
+
+    fn zero() -> Self {
+        Self::from_u128(0)
+    }
+
+    fn one() -> Self {
+        Self::from_u128(1)
+    }
+
+    fn is_zero(&self) -> bool;
+
+    /// Add two counts, returning `None` on overflow.
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    /// Multiply two counts, returning `None` on overflow.
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+    /// Convert the count to a float, losing precision for big counts.
+    fn to_f64(&self) -> f64;
+}
+
+impl FishCount for u128 {
+    fn from_u128(value: u128) -> Self {
+        value
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u128::checked_add(*self, *other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        u128::checked_mul(*self, *other)
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self as f64
+    }
+}
+
+/// Big numbers never overflow.
+impl FishCount for BigUint {
+    fn from_u128(value: u128) -> Self {
+        BigUint::from_u128(value)
+    }
+
+    fn is_zero(&self) -> bool {
+        BigUint::is_zero(self)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self.add(other))
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self.mul(other))
+    }
+
+    fn to_f64(&self) -> f64 {
+        BigUint::to_f64(self)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{count::FishCount, Overflow};
+
+/// The fish counts of a population for every simulated day.
+#[derive(Debug, PartialEq, Clone)]
+pub struct History<T> {
+    /// The fishes counted by their timer, starting with the initial population.
+    days: Vec<Vec<T>>,
+}
+
+impl<T: FishCount> History<T> {
+    /// Create a new history starting with the given fishes.
+    pub fn new(initial_fishes: Vec<T>) -> History<T> {
+        History {
+            days: vec![initial_fishes],
+        }
+    }
+
+    /// Record the fishes of the next day.
+    pub fn record(&mut self, fishes: Vec<T>) {
+        self.days.push(fishes);
+    }
+
+    /// The recorded fishes, starting with the initial population.
+    pub fn days(&self) -> &[Vec<T>] {
+        &self.days
+    }
+
+    /// The total number of fishes for every recorded day, reporting an overflow of a total.
+    pub fn fish_counts(&self) -> Result<Vec<T>, Overflow> {
+        self.days
+            .iter()
+            .map(|fishes| {
+                fishes
+                    .iter()
+                    .try_fold(T::zero(), |sum, count| sum.checked_add(count))
+                    .ok_or(Overflow)
+            })
+            .collect()
+    }
+
+    /// The growth rate from each day to the next day.
+    ///
+    /// The rate is not a number if there were no fishes on the day before.
+    pub fn growth_rates(&self) -> Vec<f64> {
+        let counts: Vec<f64> = self
+            .days
+            .iter()
+            .map(|fishes| fishes.iter().map(|count| count.to_f64()).sum())
+            .collect();
+
+        counts
+            .windows(2)
+            .map(|window| window[1] / window[0])
+            .collect()
+    }
+
+    /// Export the history as CSV.
+    ///
+    /// Every row contains the day, the fish count for each timer and the total.
+    /// Reports an overflow if a total does not fit into the number type.
+    pub fn to_csv(&self) -> Result<String, Overflow> {
+        let timer_count = self.days.iter().map(|fishes| fishes.len()).max().unwrap();
+        let mut output = "day".to_string();
+
+        for timer in 0..timer_count {
+            output += &format!(",timer_{}", timer);
+        }
+
+        output += ",total\n";
+
+        for (day, (fishes, total)) in self.days.iter().zip(self.fish_counts()?).enumerate() {
+            output += &day.to_string();
+
+            for count in fishes {
+                output += &format!(",{}", count);
+            }
+
+            output += &format!(",{}\n", total);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{history::History, Overflow};
+
+    fn example_history() -> History<u128> {
+        let mut history = History::new(vec![1, 1, 0]);
+        history.record(vec![1, 0, 2]);
+        history.record(vec![0, 4, 0]);
+        history
+    }
+
+    #[test]
+    fn should_count_fishes() {
+        let actual = example_history().fish_counts();
+
+        assert_eq!(actual, Ok(vec![2, 3, 4]));
+    }
+
+    #[test]
+    fn should_determine_growth_rates() {
+        let actual = example_history().growth_rates();
+
+        assert_eq!(actual, vec![1.5, 4.0 / 3.0]);
+    }
+
+    #[test]
+    fn should_export_csv() {
+        let expected = "day,timer_0,timer_1,timer_2,total\n\
+            0,1,1,0,2\n\
+            1,1,0,2,3\n\
+            2,0,4,0,4\n";
+        let actual = example_history().to_csv();
+
+        assert_eq!(actual, Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn should_report_overflow_in_csv() {
+        let history = History::new(vec![u128::MAX, 1]);
+
+        assert_eq!(history.to_csv(), Err(Overflow));
+    }
+}
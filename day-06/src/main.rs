@@ -1,70 +1,378 @@
-use std::fs;
+use std::{env, fs};
 
+use bignum::BigUint;
+use count::FishCount;
+use history::History;
+use matrix::Matrix;
+use stochastic::{Hazards, Rng, Trials};
+
+mod bignum;
+mod count;
+mod history;
+mod matrix;
+mod stochastic;
+
+/// Above this number of days the simulation fast-forwards with the transition matrix.
+const FAST_FORWARD_THRESHOLD: usize = 512;
+
+/// The life cycle of a fish species.
 #[derive(Debug, PartialEq, Clone, Copy)]
-struct Population {
-    fishes: [u128; 9],
+struct LifeCycle {
+    /// The timer of a fish after it gave birth.
+    reset_timer: usize,
+    /// The timer of a newborn fish.
+    newborn_timer: usize,
+    /// The number of fishes born at once.
+    litter_size: u128,
+}
+
+impl LifeCycle {
+    /// The life cycle of the lanternfish.
+    const LANTERNFISH: LifeCycle = LifeCycle::new(6, 8);
+
+    /// Create a new life cycle with a single newborn per birth.
+    const fn new(reset_timer: usize, newborn_timer: usize) -> LifeCycle {
+        LifeCycle {
+            reset_timer,
+            newborn_timer,
+            litter_size: 1,
+        }
+    }
+
+    /// Set the number of fishes born at once.
+    fn with_litter_size(self, litter_size: u128) -> LifeCycle {
+        LifeCycle {
+            litter_size,
+            ..self
+        }
+    }
+
+    /// The number of different timer values a fish can have.
+    fn timer_count(&self) -> usize {
+        self.reset_timer.max(self.newborn_timer) + 1
+    }
+
+    /// The factor the population grows by per day in the long run.
+    ///
+    /// This is the dominant eigenvalue of the transition matrix.
+    /// A fish giving birth today gives birth again after `reset_timer + 1` days,
+    /// its newborns after `newborn_timer + 1` days, so the eigenvalue `x` solves
+    /// `1 = x^-(reset_timer + 1) + litter_size * x^-(newborn_timer + 1)`.
+    fn asymptotic_growth_rate(&self) -> f64 {
+        let births = |x: f64| {
+            x.powi(-(self.reset_timer as i32 + 1))
+                + self.litter_size as f64 * x.powi(-(self.newborn_timer as i32 + 1))
+        };
+
+        // The births decrease with x, at 1 there are at least as many births as fishes
+        let mut low = 1.0;
+        let mut high = 2.0 + self.litter_size as f64;
+
+        for _ in 0..200 {
+            let mid = (low + high) / 2.0;
+
+            if births(mid) > 1.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        (low + high) / 2.0
+    }
+}
+
+/// The fish count exceeded the range of the number type.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Overflow;
+
+/// A stochastic simulation that could not be run.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum TrialError {
+    /// At least one trial is needed to summarize the runs.
+    NoTrials,
+    /// A fish count exceeded the range of the number type.
+    Overflow,
+}
+
+impl From<Overflow> for TrialError {
+    fn from(_: Overflow) -> Self {
+        TrialError::Overflow
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Population<T = u128> {
+    life_cycle: LifeCycle,
+    fishes: Vec<T>,
 }
 
 impl Population {
-    /// Create a new fish population.
-    fn new(fishes: [u128; 9]) -> Population {
-        Population { fishes }
+    /// Create a new fish population with the given life cycle.
+    ///
+    /// The fishes are counted by their timer.
+    fn with_life_cycle(life_cycle: LifeCycle, mut fishes: Vec<u128>) -> Population {
+        if fishes.len() < life_cycle.timer_count() {
+            fishes.resize(life_cycle.timer_count(), 0);
+        }
+
+        Population { life_cycle, fishes }
     }
 
-    /// Create a new fish population from a list of fish.
-    fn from_fish_list(fish_list: Vec<usize>) -> Population {
-        let mut population = Population::new([0u128; 9]);
+    /// Create a new fish population with the given life cycle from a list of fish.
+    fn from_fish_list_with_life_cycle(life_cycle: LifeCycle, fish_list: Vec<usize>) -> Population {
+        let mut population = Population::with_life_cycle(life_cycle, vec![]);
 
         for fish_time in fish_list {
+            if fish_time >= population.fishes.len() {
+                population.fishes.resize(fish_time + 1, 0);
+            }
+
             population.fishes[fish_time] += 1;
         }
 
         population
     }
 
-    /// Create a new fish population from an input list of fish times.
+    /// Create a new lanternfish population from an input list of fish times.
     fn from_input(input: String) -> Population {
+        Population::from_input_with_life_cycle(LifeCycle::LANTERNFISH, input)
+    }
+
+    /// Create a new fish population with the given life cycle from an input list of fish times.
+    fn from_input_with_life_cycle(life_cycle: LifeCycle, input: String) -> Population {
         let fish_list = input
             .trim()
-            .split(",")
-            .into_iter()
-            .map(|fish_time_str| fish_time_str.parse::<usize>())
-            .filter(|res| res.is_ok())
-            .map(|res| res.unwrap())
+            .split(',')
+            .filter_map(|fish_time_str| fish_time_str.parse::<usize>().ok())
+            .collect();
+
+        Population::from_fish_list_with_life_cycle(life_cycle, fish_list)
+    }
+
+    /// Count the number of fishes after the given number of days, modulo the given modulus.
+    ///
+    /// Returns `None` if the modulus is zero. The population itself is not changed.
+    fn fish_count_mod(&self, days: u64, modulus: u64) -> Option<u64> {
+        if modulus == 0 {
+            return None;
+        }
+
+        let fishes: Vec<u128> = self
+            .fishes
+            .iter()
+            .map(|&count| count % modulus as u128)
             .collect();
 
-        Population::from_fish_list(fish_list)
+        let count = self
+            .transition_matrix()
+            .pow_mod(days, modulus)
+            .mul_vec_mod(&fishes, modulus)
+            .into_iter()
+            .fold(0, |sum, count| (sum + count) % modulus as u128);
+
+        Some(count as u64)
     }
 
-    /// Simulate the next day.
-    fn next_day(&mut self) {
-        let fishes_giving_birth = self.fishes[0];
+    /// Simulate the next day, where fishes may die or skip their reproduction.
+    ///
+    /// On overflow, the timers may already have been passed.
+    fn next_day_stochastic(&mut self, hazards: Hazards, rng: &mut Rng) -> Result<(), Overflow> {
+        // Some fishes die before the day starts
+        for count in self.fishes.iter_mut() {
+            *count = rng.binomial(*count, 1.0 - hazards.mortality());
+        }
+
+        // Fishes skipping their reproduction still reset their timer
+        let fishes_giving_birth = rng.binomial(self.fishes[0], 1.0 - hazards.skip_reproduction());
+        let fishes_resetting = self.fishes[0];
 
         // Pass one day on the timer of each fish
-        for i in 1..9 {
-            self.fishes[i - 1] = self.fishes[i];
+        self.fishes.rotate_left(1);
+        let last = self.fishes.len() - 1;
+        self.fishes[last] = 0;
+
+        // Give birth to the new fishes
+        let newborns = fishes_giving_birth
+            .checked_mul(self.life_cycle.litter_size)
+            .ok_or(Overflow)?;
+        let newborn_timer = self.life_cycle.newborn_timer;
+        self.fishes[newborn_timer] = self.fishes[newborn_timer]
+            .checked_add(newborns)
+            .ok_or(Overflow)?;
+        // Reset timer of the fishes whose timer ran out
+        let reset_timer = self.life_cycle.reset_timer;
+        self.fishes[reset_timer] = self.fishes[reset_timer]
+            .checked_add(fishes_resetting)
+            .ok_or(Overflow)?;
+
+        Ok(())
+    }
+
+    /// Run the stochastic simulation many times and collect the final fish counts.
+    ///
+    /// The population itself is not changed.
+    fn simulate_trials(
+        &self,
+        days: usize,
+        trials: usize,
+        hazards: Hazards,
+        seed: u64,
+    ) -> Result<Trials, TrialError> {
+        if trials == 0 {
+            return Err(TrialError::NoTrials);
         }
 
+        let mut rng = Rng::new(seed);
+
+        let counts = (0..trials)
+            .map(|_| {
+                let mut population = self.clone();
+
+                for _ in 0..days {
+                    population.next_day_stochastic(hazards, &mut rng)?;
+                }
+
+                population.fish_count()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Trials::new(counts))
+    }
+}
+
+impl<T: FishCount> Population<T> {
+    /// Convert the population to count the fishes with another number type.
+    fn convert<U: FishCount>(&self, convert: impl Fn(&T) -> U) -> Population<U> {
+        Population {
+            life_cycle: self.life_cycle,
+            fishes: self.fishes.iter().map(convert).collect(),
+        }
+    }
+
+    /// Simulate the next day, reporting an overflow of the fish count.
+    ///
+    /// On overflow, the timers may already have been passed.
+    fn next_day(&mut self) -> Result<(), Overflow> {
+        let fishes_giving_birth = self.fishes[0].clone();
+        let litter_size = T::from_u128(self.life_cycle.litter_size);
+        let newborn_timer = self.life_cycle.newborn_timer;
+        let reset_timer = self.life_cycle.reset_timer;
+
+        // Pass one day on the timer of each fish
+        self.fishes.rotate_left(1);
+        let last = self.fishes.len() - 1;
+        self.fishes[last] = T::zero();
+
         // Give birth to the new fishes
-        self.fishes[8] = fishes_giving_birth;
+        let newborns = fishes_giving_birth
+            .checked_mul(&litter_size)
+            .ok_or(Overflow)?;
+        self.fishes[newborn_timer] = self.fishes[newborn_timer]
+            .checked_add(&newborns)
+            .ok_or(Overflow)?;
         // Reset timer of the fishes that gave birth
-        self.fishes[6] += fishes_giving_birth;
+        self.fishes[reset_timer] = self.fishes[reset_timer]
+            .checked_add(&fishes_giving_birth)
+            .ok_or(Overflow)?;
+
+        Ok(())
+    }
+
+    /// The matrix describing the change of the fish timers in a single day.
+    fn transition_matrix(&self) -> Matrix<T> {
+        let mut transitions = Matrix::<u128>::zero(self.fishes.len());
+
+        // Pass one day on the timer of each fish
+        for timer in 1..self.fishes.len() {
+            transitions.set(timer - 1, timer, 1);
+        }
+
+        // Fishes with timer 0 give birth and reset their timer
+        let newborn_timer = self.life_cycle.newborn_timer;
+        let reset_timer = self.life_cycle.reset_timer;
+        transitions.set(
+            newborn_timer,
+            0,
+            transitions.get(newborn_timer, 0) + self.life_cycle.litter_size,
+        );
+        transitions.set(reset_timer, 0, transitions.get(reset_timer, 0) + 1);
+
+        let mut matrix = Matrix::zero(self.fishes.len());
+
+        for row in 0..self.fishes.len() {
+            for col in 0..self.fishes.len() {
+                matrix.set(row, col, T::from_u128(*transitions.get(row, col)));
+            }
+        }
+
+        matrix
+    }
+
+    /// Simulate the given number of days at once by exponentiating the transition matrix.
+    ///
+    /// On overflow, the population is left unchanged.
+    fn fast_forward(&mut self, days: u64) -> Result<(), Overflow> {
+        self.fishes = self
+            .transition_matrix()
+            .checked_pow(days)
+            .and_then(|matrix| matrix.checked_mul_vec(&self.fishes))
+            .ok_or(Overflow)?;
+
+        Ok(())
+    }
+
+    /// Simulate the population day by day, recording the fishes of every day.
+    ///
+    /// On overflow, the population is left unchanged.
+    fn simulate_history(&mut self, days: usize) -> Result<History<T>, Overflow> {
+        let mut population = self.clone();
+        let mut history = History::new(population.fishes.clone());
+
+        for _ in 0..days {
+            population.next_day()?;
+            history.record(population.fishes.clone());
+        }
+
+        *self = population;
+
+        Ok(history)
     }
 
     /// Simulate the population for the given number of days.
     ///
     /// Returns the fish population at the end.
-    fn simulate_days(&mut self, days: usize) -> u128 {
-        for _ in 0..days {
-            self.next_day();
+    /// On overflow, the population is left unchanged.
+    ///
+    /// If the matrix power overflows, the days are simulated one by one instead.
+    /// That loop stops at the first overflow of the population, which for a population
+    /// with any fishes follows soon after the matrix power overflows, as both grow alike.
+    fn simulate_days(&mut self, days: usize) -> Result<T, Overflow> {
+        // An empty population stays empty, no matter how large the matrix power gets
+        if self.fishes.iter().all(|count| count.is_zero()) {
+            return Ok(T::zero());
+        }
+
+        // The matrix power can overflow even if the population itself stays small
+        if days <= FAST_FORWARD_THRESHOLD || self.fast_forward(days as u64).is_err() {
+            let mut population = self.clone();
+
+            for _ in 0..days {
+                population.next_day()?;
+            }
+
+            *self = population;
         }
 
         self.fish_count()
     }
 
-    /// Count the number of fishes.
-    fn fish_count(&self) -> u128 {
-        self.fishes.into_iter().sum()
+    /// Count the number of fishes, reporting an overflow of the count.
+    fn fish_count(&self) -> Result<T, Overflow> {
+        self.fishes
+            .iter()
+            .try_fold(T::zero(), |sum, count| sum.checked_add(count))
+            .ok_or(Overflow)
     }
 }
 
@@ -72,28 +380,125 @@ fn main() {
     let filename = "./input/input.txt";
     let input = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
-    let mut population = Population::from_input(input);
+    let initial = Population::from_input(input);
+    let mut population = initial.clone();
 
-    population.simulate_days(80);
-    let count_80_days = population.fish_count();
+    // Optionally export the history of the population
+    if let Some(path) = env::args().nth(1) {
+        let history = population
+            .clone()
+            .simulate_history(256)
+            .expect("Too many fish to count");
+        let csv = history.to_csv().expect("Too many fish to count");
+
+        fs::write(&path, csv).expect("Something went wrong writing the history");
+        println!("History written to {}", path);
+
+        if let Some(growth_rate) = history.growth_rates().last() {
+            println!("On day 256, the population grew by {:.6}", growth_rate);
+        }
+        if let Some(fishes) = history.days().last() {
+            println!("On day 256, the fishes by timer are {:?}", fishes);
+        }
+    }
+
+    let count_80_days = population
+        .simulate_days(80)
+        .expect("Too many fish to count");
 
     println!("There are {} fish after 80 days!", count_80_days);
 
-    population.simulate_days(256 - 80);
-    let count_256_days = population.fish_count();
+    let count_256_days = population
+        .simulate_days(256 - 80)
+        .expect("Too many fish to count");
 
     println!("There are {} fish after 256 days!", count_256_days);
+
+    let growth_rate = LifeCycle::LANTERNFISH.asymptotic_growth_rate();
+    println!(
+        "In the long run, the population grows by {:.6} per day",
+        growth_rate
+    );
+
+    // Far beyond 256 days the counts only fit into big numbers
+    let count_1000_days = initial
+        .convert(|&count| BigUint::from_u128(count))
+        .simulate_days(1000)
+        .expect("Big numbers never overflow");
+
+    println!(
+        "There are {} fish after 1000 days, which {} into 128 bits!",
+        count_1000_days,
+        if count_1000_days.to_u128().is_some() {
+            "fits"
+        } else {
+            "does not fit"
+        }
+    );
+
+    let modulus = 1_000_000_007;
+    let count_mod = initial
+        .fish_count_mod(10u64.pow(15), modulus)
+        .expect("The modulus is not zero");
+
+    println!(
+        "There are {} fish modulo {} after 10^15 days!",
+        count_mod, modulus
+    );
+
+    // Repeat the first 80 days with some fishes dying or skipping their reproduction
+    let hazards = Hazards::new(0.01, 0.1).expect("The probabilities are valid");
+    let trials = initial
+        .simulate_trials(80, 100, hazards, 6)
+        .expect("Too many fish to count");
+    let (low, high) = trials.band(0.9);
+
+    println!(
+        "With hazards, there are {:.0} ± {:.0} fish after 80 days, 90% between {:.0} and {:.0}",
+        trials.mean(),
+        trials.variance().sqrt(),
+        low,
+        high
+    );
+
+    if let (Some(fewest), Some(most)) = (trials.counts().first(), trials.counts().last()) {
+        println!("The trials range from {} to {} fish", fewest, most);
+    }
+
+    // Optionally compare with a species giving birth to more fishes at once
+    if let Some(litter_size) = env::args().nth(2) {
+        let litter_size = litter_size
+            .parse()
+            .expect("Something went wrong parsing the litter size");
+        let life_cycle = LifeCycle::LANTERNFISH.with_litter_size(litter_size);
+        let count = Population::with_life_cycle(life_cycle, initial.fishes.clone())
+            .simulate_days(80)
+            .expect("Too many fish to count");
+
+        println!(
+            "With litters of {}, there are {} fish after 80 days, growing by {:.6} per day",
+            litter_size,
+            count,
+            life_cycle.asymptotic_growth_rate()
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Population;
+    use crate::{bignum::BigUint, Hazards, LifeCycle, Overflow, Population, Rng, TrialError};
+
+    /// A lanternfish population with the given fishes counted by their timer.
+    fn lanternfish(fishes: [u128; 9]) -> Population {
+        Population::with_life_cycle(LifeCycle::LANTERNFISH, fishes.to_vec())
+    }
+
     #[test]
     fn should_create_from_fish_list() {
         // Population 3,4,3,1,2
         let fish_list = vec![3, 4, 3, 1, 2];
-        let expected = Population::new([0, 1, 1, 2, 1, 0, 0, 0, 0]);
-        let actual = Population::from_fish_list(fish_list);
+        let expected = lanternfish([0, 1, 1, 2, 1, 0, 0, 0, 0]);
+        let actual = Population::from_fish_list_with_life_cycle(LifeCycle::LANTERNFISH, fish_list);
 
         assert_eq!(actual, expected);
     }
@@ -101,7 +506,7 @@ mod tests {
     #[test]
     fn should_create_from_input() {
         let input = "3,4,3,1,2".to_string();
-        let expected = Population::new([0, 1, 1, 2, 1, 0, 0, 0, 0]);
+        let expected = lanternfish([0, 1, 1, 2, 1, 0, 0, 0, 0]);
         let actual = Population::from_input(input);
 
         assert_eq!(actual, expected);
@@ -110,10 +515,272 @@ mod tests {
     #[test]
     fn should_simulate_fish_population() {
         let mut population = Population::from_input("3,4,3,1,2\n".into());
-        let population_18_days = population.simulate_days(18);
-        let population_80_days = population.simulate_days(80 - 18);
+        let population_18_days = population.simulate_days(18).unwrap();
+        let population_80_days = population.simulate_days(80 - 18).unwrap();
 
         assert_eq!(population_18_days, 26);
         assert_eq!(population_80_days, 5934);
     }
+
+    #[test]
+    fn should_fast_forward_like_daily_simulation() {
+        let life_cycles = [
+            LifeCycle::LANTERNFISH,
+            LifeCycle::new(1, 2).with_litter_size(2),
+            LifeCycle::new(4, 2),
+        ];
+
+        for life_cycle in life_cycles {
+            for days in 0..100 {
+                let mut expected =
+                    Population::from_input_with_life_cycle(life_cycle, "3,4,3,1,2".into());
+                let mut actual = expected.clone();
+
+                for _ in 0..days {
+                    expected.next_day().unwrap();
+                }
+                actual.fast_forward(days).unwrap();
+
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn should_simulate_long_periods_with_fast_forward() {
+        let mut population = Population::from_input("3,4,3,1,2\n".into());
+        let population_256_days = population.simulate_days(256).unwrap();
+
+        assert_eq!(population_256_days, 26984457539);
+
+        let mut expected = population.clone();
+        for _ in 0..600 {
+            expected.next_day().unwrap();
+        }
+
+        assert_eq!(population.simulate_days(600), expected.fish_count());
+    }
+
+    #[test]
+    fn should_count_fishes_modulo() {
+        let population = Population::from_input("3,4,3,1,2\n".into());
+        let modulus = 1_000_000_007;
+
+        assert_eq!(
+            population.fish_count_mod(256, modulus),
+            Some(26984457539 % modulus)
+        );
+        // Does not overflow for huge numbers of days
+        assert!(population.fish_count_mod(10u64.pow(15), modulus).unwrap() < modulus);
+        assert_eq!(population.fish_count_mod(256, 0), None);
+    }
+
+    #[test]
+    fn should_count_fishes_modulo_close_to_u64() {
+        let population = lanternfish([u64::MAX as u128 - 60; 9]);
+
+        assert_eq!(
+            population.fish_count_mod(100_000, u64::MAX - 58),
+            Some(9872231455694729939)
+        );
+    }
+
+    #[test]
+    fn should_report_overflow_when_fast_forwarding() {
+        let mut population = Population::from_input("3,4,3,1,2\n".into());
+        let before = population.clone();
+
+        assert_eq!(population.simulate_days(3000), Err(Overflow));
+        assert_eq!(population, before);
+    }
+
+    #[test]
+    fn should_simulate_empty_population_for_any_days() {
+        let mut population = lanternfish([0; 9]);
+
+        assert_eq!(population.simulate_days(500), Ok(0));
+        assert_eq!(population.simulate_days(10usize.pow(12)), Ok(0));
+    }
+
+    #[test]
+    fn should_stop_daily_simulation_at_overflow_when_matrix_overflows() {
+        // The population overflows after about a thousand days in the daily loop
+        let mut population = lanternfish([0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        let before = population.clone();
+
+        assert_eq!(population.simulate_days(10usize.pow(12)), Err(Overflow));
+        assert_eq!(population, before);
+    }
+
+    #[test]
+    fn should_report_overflow_in_daily_simulation() {
+        let mut population = lanternfish([u128::MAX, 0, 0, 0, 0, 0, 0, 1, 0]);
+        let before = population.clone();
+
+        assert_eq!(population.simulate_days(10), Err(Overflow));
+        assert_eq!(population, before);
+    }
+
+    #[test]
+    fn should_count_big_populations_exactly() {
+        let population = Population::from_input("3,4,3,1,2\n".into());
+        let mut big_population = population.convert(|&count| BigUint::from_u128(count));
+        let mut expected = big_population.clone();
+
+        let count_256_days = big_population.simulate_days(256).unwrap();
+        assert_eq!(count_256_days.to_string(), "26984457539");
+
+        // Cross-check the fast path against the daily simulation
+        let count_3000_days = big_population.simulate_days(3000 - 256).unwrap();
+        for _ in 0..3000 {
+            expected.next_day().unwrap();
+        }
+
+        assert_eq!(count_3000_days, expected.fish_count().unwrap());
+        assert_eq!(count_3000_days.to_u128(), None);
+    }
+
+    #[test]
+    fn should_simulate_history() {
+        let mut population = Population::from_input("3,4,3,1,2\n".into());
+        let history = population.simulate_history(18).unwrap();
+
+        assert_eq!(history.days().len(), 19);
+        assert_eq!(history.days()[0], vec![0, 1, 1, 2, 1, 0, 0, 0, 0]);
+        assert_eq!(history.days()[2], vec![1, 2, 1, 0, 0, 0, 1, 0, 1]);
+        assert_eq!(history.fish_counts().unwrap()[18], 26);
+        assert_eq!(history.days()[18], population.fishes);
+    }
+
+    #[test]
+    fn should_report_overflow_in_history() {
+        let mut population = lanternfish([u128::MAX, 0, 0, 0, 0, 0, 0, 1, 0]);
+        let expected = population.clone();
+
+        assert_eq!(population.simulate_history(10), Err(Overflow));
+        assert_eq!(population, expected);
+    }
+
+    #[test]
+    fn should_determine_asymptotic_growth_rate() {
+        let rate = LifeCycle::LANTERNFISH.asymptotic_growth_rate();
+
+        // Root of the characteristic polynomial x^9 - x^2 - 1
+        assert!((rate.powi(9) - rate.powi(2) - 1.0).abs() < 1e-12);
+
+        let mut population = Population::from_input("3,4,3,1,2\n".into());
+        let growth_rates = population.simulate_history(500).unwrap().growth_rates();
+
+        // The daily rates still oscillate a bit, so average over the last 100 days
+        let average_rate = growth_rates[400..].iter().product::<f64>().powf(0.01);
+
+        assert!((average_rate - rate).abs() < 1e-4);
+    }
+
+    #[test]
+    fn should_determine_asymptotic_growth_rate_of_custom_life_cycle() {
+        // Every fish splits into three each day
+        let life_cycle = LifeCycle::new(0, 0).with_litter_size(2);
+
+        assert!((life_cycle.asymptotic_growth_rate() - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn should_simulate_stochastic_day_without_hazards_like_deterministic_day() {
+        let mut expected = Population::from_input("3,4,3,1,2\n".into());
+        let mut actual = expected.clone();
+        let mut rng = Rng::new(1);
+
+        for _ in 0..80 {
+            expected.next_day().unwrap();
+            actual
+                .next_day_stochastic(Hazards::new(0.0, 0.0).unwrap(), &mut rng)
+                .unwrap();
+        }
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_simulate_stochastic_extinction() {
+        let population = Population::from_input("3,4,3,1,2\n".into());
+        let hazards = Hazards::new(1.0, 0.0).unwrap();
+        let trials = population.simulate_trials(10, 5, hazards, 1).unwrap();
+
+        assert_eq!(trials.counts(), &[0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn should_simulate_stochastic_trials() {
+        let population = Population::from_input("3,4,3,1,2\n".into());
+        let hazards = Hazards::new(0.01, 0.2).unwrap();
+        let trials = population.simulate_trials(80, 200, hazards, 42).unwrap();
+
+        // Reproducible with the same seed
+        assert_eq!(
+            Ok(trials.clone()),
+            population.simulate_trials(80, 200, hazards, 42)
+        );
+
+        // The hazards slow down the growth compared to the deterministic model
+        let (low, high) = trials.band(0.9);
+        assert!(low <= trials.percentile(0.5) && trials.percentile(0.5) <= high);
+        assert!(trials.mean() < 5934.0);
+        assert!(trials.variance() > 0.0);
+    }
+
+    #[test]
+    fn should_report_stochastic_errors() {
+        let population = Population::from_input("3,4,3,1,2\n".into());
+        let hazards = Hazards::new(0.0, 0.0).unwrap();
+
+        assert_eq!(
+            population.simulate_trials(10, 0, hazards, 1),
+            Err(TrialError::NoTrials)
+        );
+
+        // Every birth overflows the newborn count
+        let life_cycle = LifeCycle::LANTERNFISH.with_litter_size(u128::MAX);
+        let population = Population::from_input_with_life_cycle(life_cycle, "0,0".into());
+
+        assert_eq!(
+            population.simulate_trials(1, 1, hazards, 1),
+            Err(TrialError::Overflow)
+        );
+    }
+
+    #[test]
+    fn should_create_lanternfish_preset() {
+        let life_cycle = LifeCycle::new(6, 8);
+
+        assert_eq!(life_cycle, LifeCycle::LANTERNFISH);
+        assert_eq!(life_cycle.timer_count(), 9);
+    }
+
+    #[test]
+    fn should_create_from_fish_list_with_life_cycle() {
+        let life_cycle = LifeCycle::new(2, 3);
+        let fish_list = vec![0, 1, 5];
+        let expected = Population::with_life_cycle(life_cycle, vec![1, 1, 0, 0, 0, 1]);
+        let actual = Population::from_fish_list_with_life_cycle(life_cycle, fish_list);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_simulate_custom_life_cycle() {
+        // Reset to 1, newborns start at 2 and come in pairs
+        let life_cycle = LifeCycle::new(1, 2).with_litter_size(2);
+        let mut population = Population::from_input_with_life_cycle(life_cycle, "0".into());
+
+        population.next_day().unwrap();
+        assert_eq!(population.fishes, vec![0, 1, 2]);
+
+        population.next_day().unwrap();
+        assert_eq!(population.fishes, vec![1, 2, 0]);
+
+        population.next_day().unwrap();
+        assert_eq!(population.fishes, vec![2, 1, 2]);
+        assert_eq!(population.fish_count(), Ok(5));
+    }
 }
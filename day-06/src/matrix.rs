@@ -0,0 +1,230 @@
+use crate::count::FishCount;
+
+/// A square matrix of fish counts.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Matrix<T> {
+    size: usize,
+    /// The values row by row.
+    values: Vec<T>,
+}
+
+impl<T: FishCount> Matrix<T> {
+    /// Create a new matrix filled with zeros.
+    pub fn zero(size: usize) -> Matrix<T> {
+        Matrix {
+            size,
+            values: vec![T::zero(); size * size],
+        }
+    }
+
+    /// Create a new identity matrix.
+    pub fn identity(size: usize) -> Matrix<T> {
+        let mut matrix = Matrix::zero(size);
+
+        for i in 0..size {
+            matrix.set(i, i, T::one());
+        }
+
+        matrix
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.values[row * self.size + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) {
+        self.values[row * self.size + col] = value;
+    }
+
+    /// Multiply two matrices, reducing every entry with the given function.
+    ///
+    /// Returns `None` on overflow.
+    fn mul_with(&self, other: &Matrix<T>, reduce: impl Fn(T) -> T) -> Option<Matrix<T>> {
+        assert_eq!(self.size, other.size, "Matrix sizes do not match");
+        let mut product: Matrix<T> = Matrix::zero(self.size);
+
+        for row in 0..self.size {
+            for k in 0..self.size {
+                let factor = self.get(row, k);
+
+                if factor.is_zero() {
+                    continue;
+                }
+
+                for col in 0..self.size {
+                    let term = reduce(factor.checked_mul(other.get(k, col))?);
+                    let value = reduce(product.get(row, col).checked_add(&term)?);
+                    product.set(row, col, value);
+                }
+            }
+        }
+
+        Some(product)
+    }
+
+    /// Multiply two matrices.
+    ///
+    /// Returns `None` on overflow.
+    pub fn checked_mul(&self, other: &Matrix<T>) -> Option<Matrix<T>> {
+        self.mul_with(other, |value| value)
+    }
+
+    /// Raise the matrix to the given power by repeated squaring.
+    ///
+    /// Returns `None` on overflow.
+    pub fn checked_pow(&self, exponent: u64) -> Option<Matrix<T>> {
+        self.pow_with(exponent, |a, b| a.checked_mul(b))
+    }
+
+    fn pow_with(
+        &self,
+        mut exponent: u64,
+        mul: impl Fn(&Matrix<T>, &Matrix<T>) -> Option<Matrix<T>>,
+    ) -> Option<Matrix<T>> {
+        let mut result = Matrix::identity(self.size);
+        let mut base = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = mul(&result, &base)?;
+            }
+
+            exponent >>= 1;
+
+            if exponent > 0 {
+                base = mul(&base, &base)?;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Multiply the matrix with a column vector.
+    ///
+    /// Returns `None` on overflow.
+    pub fn checked_mul_vec(&self, vector: &[T]) -> Option<Vec<T>> {
+        assert_eq!(self.size, vector.len(), "Vector size does not match");
+
+        (0..self.size)
+            .map(|row| {
+                (0..self.size).try_fold(T::zero(), |sum, col| {
+                    sum.checked_add(&self.get(row, col).checked_mul(&vector[col])?)
+                })
+            })
+            .collect()
+    }
+}
+
+impl Matrix<u128> {
+    /// Multiply two matrices modulo the given modulus.
+    pub fn mul_mod(&self, other: &Matrix<u128>, modulus: u64) -> Matrix<u128> {
+        // The entries stay below the modulus, so their products fit into `u128`
+        self.mul_with(other, |value| value % modulus as u128)
+            .unwrap()
+    }
+
+    /// Raise the matrix to the given power modulo the given modulus.
+    pub fn pow_mod(&self, exponent: u64, modulus: u64) -> Matrix<u128> {
+        let reduced = self.mul_mod(&Matrix::identity(self.size), modulus);
+
+        reduced
+            .pow_with(exponent, |a, b| Some(a.mul_mod(b, modulus)))
+            .unwrap()
+    }
+
+    /// Multiply the matrix with a column vector modulo the given modulus.
+    ///
+    /// The entries of both must already be reduced.
+    pub fn mul_vec_mod(&self, vector: &[u128], modulus: u64) -> Vec<u128> {
+        assert_eq!(self.size, vector.len(), "Vector size does not match");
+
+        // Reduce after every step, a sum of several products could exceed `u128`
+        (0..self.size)
+            .map(|row| {
+                (0..self.size).fold(0, |sum, col| {
+                    let term = self.get(row, col) * vector[col] % modulus as u128;
+                    (sum + term) % modulus as u128
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bignum::BigUint, matrix::Matrix};
+
+    fn fibonacci_matrix() -> Matrix<u128> {
+        let mut matrix = Matrix::zero(2);
+        matrix.set(0, 0, 1);
+        matrix.set(0, 1, 1);
+        matrix.set(1, 0, 1);
+        matrix
+    }
+
+    #[test]
+    fn should_raise_matrix_to_power() {
+        let actual = fibonacci_matrix().checked_pow(10).unwrap();
+
+        assert_eq!(*actual.get(0, 1), 55);
+        assert_eq!(*actual.get(0, 0), 89);
+    }
+
+    #[test]
+    fn should_raise_matrix_to_zero_power() {
+        let actual = fibonacci_matrix().checked_pow(0).unwrap();
+
+        assert_eq!(actual, Matrix::identity(2));
+    }
+
+    #[test]
+    fn should_report_overflow() {
+        // The n-th power contains F(n + 1), and F(187) does not fit into u128 anymore
+        assert!(fibonacci_matrix().checked_pow(185).is_some());
+        assert_eq!(fibonacci_matrix().checked_pow(186), None);
+    }
+
+    #[test]
+    fn should_raise_big_matrix_to_power() {
+        let mut matrix = Matrix::zero(2);
+        matrix.set(0, 0, BigUint::from_u128(1));
+        matrix.set(0, 1, BigUint::from_u128(1));
+        matrix.set(1, 0, BigUint::from_u128(1));
+
+        let actual = matrix.checked_pow(200).unwrap();
+
+        assert_eq!(
+            actual.get(0, 1).to_string(),
+            "280571172992510140037611932413038677189525"
+        );
+    }
+
+    #[test]
+    fn should_raise_matrix_to_power_modulo() {
+        // F(90) = 2880067194370816120
+        let actual = fibonacci_matrix().pow_mod(90, 1_000_000_007);
+
+        assert_eq!(*actual.get(0, 1), 2880067194370816120 % 1_000_000_007);
+    }
+
+    #[test]
+    fn should_multiply_vector_modulo() {
+        let modulus = u64::MAX - 58;
+        let entry = modulus as u128 - 1;
+        let mut matrix = Matrix::zero(2);
+        matrix.set(0, 0, entry);
+        matrix.set(0, 1, entry);
+
+        // (-1) * (-1) + (-1) * (-1) = 2
+        let actual = matrix.mul_vec_mod(&[entry, entry], modulus);
+
+        assert_eq!(actual, vec![2, 0]);
+    }
+
+    #[test]
+    fn should_multiply_vector() {
+        let actual = fibonacci_matrix().checked_mul_vec(&[3, 2]);
+
+        assert_eq!(actual, Some(vec![5, 3]));
+    }
+}
@@ -0,0 +1,232 @@
+/// Above this number of trials, binomial samples are approximated with a normal distribution.
+const EXACT_BINOMIAL_LIMIT: u128 = 1000;
+
+/// A seeded pseudo random number generator (SplitMix64).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed float in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A standard normally distributed float, using the Box-Muller transform.
+    pub fn next_normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// The number of successes in `n` trials with the given probability.
+    ///
+    /// For many trials, the result is approximated with a normal distribution.
+    pub fn binomial(&mut self, n: u128, probability: f64) -> u128 {
+        if probability <= 0.0 {
+            return 0;
+        }
+        if probability >= 1.0 {
+            return n;
+        }
+
+        if n <= EXACT_BINOMIAL_LIMIT {
+            (0..n).filter(|_| self.next_f64() < probability).count() as u128
+        } else {
+            let mean = n as f64 * probability;
+            let deviation = (mean * (1.0 - probability)).sqrt();
+            let sample = (mean + deviation * self.next_normal()).round();
+
+            (sample.max(0.0) as u128).min(n)
+        }
+    }
+}
+
+/// A probability that is not within `0.0..=1.0`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct InvalidProbability(pub f64);
+
+/// The chances of a fish to die or to skip its reproduction.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Hazards {
+    /// The probability of a fish dying on a single day.
+    mortality: f64,
+    /// The probability of a fish not giving birth when its timer runs out.
+    skip_reproduction: f64,
+}
+
+impl Hazards {
+    /// Create the hazards, both probabilities must be within `0.0..=1.0`.
+    pub fn new(mortality: f64, skip_reproduction: f64) -> Result<Hazards, InvalidProbability> {
+        for probability in [mortality, skip_reproduction] {
+            // Also rejects NaN
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(InvalidProbability(probability));
+            }
+        }
+
+        Ok(Hazards {
+            mortality,
+            skip_reproduction,
+        })
+    }
+
+    pub fn mortality(&self) -> f64 {
+        self.mortality
+    }
+
+    pub fn skip_reproduction(&self) -> f64 {
+        self.skip_reproduction
+    }
+}
+
+/// The fish counts of many simulation runs.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trials {
+    /// The fish count of every run, in ascending order.
+    counts: Vec<u128>,
+}
+
+impl Trials {
+    /// Create the trials from the fish counts of the runs.
+    ///
+    /// Panics if no counts are given.
+    pub fn new(mut counts: Vec<u128>) -> Trials {
+        assert!(!counts.is_empty(), "At least one trial is needed");
+        counts.sort_unstable();
+        Trials { counts }
+    }
+
+    pub fn counts(&self) -> &[u128] {
+        &self.counts
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.counts.iter().map(|&count| count as f64).sum::<f64>() / self.counts.len() as f64
+    }
+
+    /// The sample variance of the fish counts.
+    pub fn variance(&self) -> f64 {
+        if self.counts.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+        let squares: f64 = self
+            .counts
+            .iter()
+            .map(|&count| (count as f64 - mean).powi(2))
+            .sum();
+
+        squares / (self.counts.len() - 1) as f64
+    }
+
+    /// The fish count below which the given fraction of runs lies.
+    ///
+    /// Interpolates linearly between the closest runs.
+    pub fn percentile(&self, fraction: f64) -> f64 {
+        let position = fraction.clamp(0.0, 1.0) * (self.counts.len() - 1) as f64;
+        let index = position.floor() as usize;
+        let next = (index + 1).min(self.counts.len() - 1);
+        let weight = position - index as f64;
+
+        self.counts[index] as f64 * (1.0 - weight) + self.counts[next] as f64 * weight
+    }
+
+    /// The central band containing the given fraction of runs.
+    ///
+    /// For example, a coverage of `0.9` gives the 5th and the 95th percentile.
+    pub fn band(&self, coverage: f64) -> (f64, f64) {
+        let tail = (1.0 - coverage) / 2.0;
+
+        (self.percentile(tail), self.percentile(1.0 - tail))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stochastic::{Hazards, InvalidProbability, Rng, Trials};
+
+    #[test]
+    fn should_generate_reproducible_numbers() {
+        let mut rng_a = Rng::new(42);
+        let mut rng_b = Rng::new(42);
+        let mut rng_c = Rng::new(43);
+
+        let a: Vec<u64> = (0..10).map(|_| rng_a.next_u64()).collect();
+        let b: Vec<u64> = (0..10).map(|_| rng_b.next_u64()).collect();
+        let c: Vec<u64> = (0..10).map(|_| rng_c.next_u64()).collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn should_match_reference_splitmix64() {
+        let mut rng = Rng::new(0);
+
+        assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+        assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+    }
+
+    #[test]
+    fn should_generate_uniform_floats() {
+        let mut rng = Rng::new(3);
+        let samples: Vec<f64> = (0..10000).map(|_| rng.next_f64()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+
+        assert!(samples.iter().all(|&sample| (0.0..1.0).contains(&sample)));
+        assert!((mean - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn should_sample_binomial() {
+        let mut rng = Rng::new(7);
+
+        assert_eq!(rng.binomial(100, 0.0), 0);
+        assert_eq!(rng.binomial(100, 1.0), 100);
+
+        // Exact sampling
+        let small: u128 = (0..1000).map(|_| rng.binomial(10, 0.3)).sum();
+        assert!((small as f64 / 1000.0 - 3.0).abs() < 0.1);
+
+        // Normal approximation
+        let big: u128 = (0..1000).map(|_| rng.binomial(1_000_000, 0.3)).sum();
+        assert!((big as f64 / 1000.0 - 300_000.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn should_reject_invalid_probabilities() {
+        assert!(Hazards::new(0.0, 1.0).is_ok());
+        assert_eq!(Hazards::new(1.5, 0.0), Err(InvalidProbability(1.5)));
+        assert_eq!(Hazards::new(0.0, -0.1), Err(InvalidProbability(-0.1)));
+        assert!(Hazards::new(f64::NAN, 0.0).is_err());
+    }
+
+    #[test]
+    fn should_summarize_trials() {
+        let trials = Trials::new(vec![4, 1, 3, 2, 5]);
+
+        assert_eq!(trials.counts(), &[1, 2, 3, 4, 5]);
+        assert_eq!(trials.mean(), 3.0);
+        assert_eq!(trials.variance(), 2.5);
+        assert_eq!(trials.percentile(0.5), 3.0);
+        assert!((trials.percentile(0.1) - 1.4).abs() < 1e-12);
+        assert_eq!(trials.band(0.5), (2.0, 4.0));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{bounds, fuel::FuelCost, total_fuel_cost, AlignmentError};
+
+/// The total fuel cost at every position between the outermost crabs.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CostCurve {
+    /// The position and its total fuel cost, `None` where the cost overflows.
+    points: Vec<(i64, Option<u128>)>,
+}
+
+impl CostCurve {
+    /// Calculate the cost curve for the given crabs.
+    pub fn new<F: FuelCost + ?Sized>(crabs: &[i64], fuel: &F) -> Result<CostCurve, AlignmentError> {
+        let (min, max) = bounds(crabs)?;
+        let points = (min..=max)
+            .map(|pos| (pos, total_fuel_cost(crabs, fuel, pos)))
+            .collect();
+
+        Ok(CostCurve { points })
+    }
+
+    /// Export the curve as CSV, leaving the fuel empty where it overflows.
+    pub fn to_csv(&self) -> String {
+        let mut output = "position,fuel\n".to_string();
+
+        for (pos, fuel_cost) in &self.points {
+            match fuel_cost {
+                Some(fuel_cost) => output += &format!("{},{}\n", pos, fuel_cost),
+                None => output += &format!("{},\n", pos),
+            }
+        }
+
+        output
+    }
+
+    /// Draw the curve as ASCII line chart with the given number of columns and rows.
+    ///
+    /// If there are more positions than columns, each column shows the cheapest position
+    /// it covers. The optimum is marked with `o` and overflowing costs with `^`.
+    pub fn to_chart(&self, width: usize, height: usize) -> String {
+        let width = width.clamp(1, self.points.len());
+        let height = height.max(2);
+
+        // The cheapest cost within every column
+        let columns: Vec<Option<u128>> = (0..width)
+            .map(|col| {
+                let start = col * self.points.len() / width;
+                let end = (col + 1) * self.points.len() / width;
+
+                self.points[start..end]
+                    .iter()
+                    .filter_map(|(_, fuel_cost)| *fuel_cost)
+                    .min()
+            })
+            .collect();
+
+        let costs = columns.iter().filter_map(|&fuel_cost| fuel_cost);
+        let min_cost = costs.clone().min().unwrap_or(0);
+        let max_cost = costs.max().unwrap_or(0);
+        let optimum = columns
+            .iter()
+            .position(|&fuel_cost| fuel_cost == Some(min_cost));
+
+        // The row of every column, counted from the bottom
+        let levels: Vec<usize> = columns
+            .iter()
+            .map(|fuel_cost| match fuel_cost {
+                Some(_) if max_cost == min_cost => 0,
+                Some(fuel_cost) => {
+                    let fraction = (fuel_cost - min_cost) as f64 / (max_cost - min_cost) as f64;
+                    (fraction * (height - 1) as f64).round() as usize
+                }
+                None => height - 1,
+            })
+            .collect();
+
+        let mut grid = vec![vec![' '; width]; height];
+
+        for (col, &level) in levels.iter().enumerate() {
+            // Connect to the previous column
+            if col > 0 {
+                let previous = levels[col - 1];
+
+                let (low, high) = (level.min(previous), level.max(previous));
+
+                for row in grid.iter_mut().take(high).skip(low + 1) {
+                    row[col] = '|';
+                }
+            }
+
+            grid[level][col] = if columns[col].is_none() {
+                '^'
+            } else if Some(col) == optimum {
+                'o'
+            } else {
+                '*'
+            };
+        }
+
+        let max_label = max_cost.to_string();
+        let min_label = min_cost.to_string();
+        let label_width = max_label.len().max(min_label.len());
+        let mut output = "".to_string();
+
+        for row in (0..height).rev() {
+            let label = if row == height - 1 {
+                &max_label
+            } else if row == 0 {
+                &min_label
+            } else {
+                ""
+            };
+
+            output += &format!("{:>label_width$} |", label);
+            output.extend(grid[row].iter());
+            output = output.trim_end().to_string() + "\n";
+        }
+
+        let first = self.points[0].0.to_string();
+        let last = self.points[self.points.len() - 1].0.to_string();
+
+        output += &format!("{:>label_width$} +{}\n", "", "-".repeat(width));
+        output += &format!(
+            "{:>label_width$}  {}{:>gap$}\n",
+            "",
+            first,
+            last,
+            gap = width.saturating_sub(first.len()).max(last.len() + 1)
+        );
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        curve::CostCurve,
+        fuel::{Linear, Polynomial, Triangular},
+        tests::determine_optimal_position,
+    };
+
+    #[test]
+    fn should_calculate_cost_curve() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let curve = CostCurve::new(&crabs, &Triangular).unwrap();
+        let (optimum, fuel_cost) = determine_optimal_position(&crabs, &Triangular).unwrap();
+
+        assert_eq!(curve.points.len(), 17);
+        assert_eq!(curve.points[0], (0, Some(290)));
+        assert_eq!(curve.points[optimum as usize], (5, Some(fuel_cost)));
+        assert!(curve
+            .points
+            .iter()
+            .all(|&(_, cost)| cost >= Some(fuel_cost)));
+    }
+
+    #[test]
+    fn should_export_cost_curve_as_csv() {
+        let crabs = vec![0, 2];
+        let expected = "position,fuel\n0,2\n1,2\n2,2\n";
+        let actual = CostCurve::new(&crabs, &Linear).unwrap().to_csv();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_leave_overflowing_costs_empty_in_csv() {
+        let crabs = vec![0, 2];
+        // A single step costs a lot, two steps cost more than fits into 128 bits
+        let mut coefficients = vec![0; 66];
+        coefficients[65] = u64::MAX;
+        let fuel = Polynomial::new(coefficients);
+        let expected = "position,fuel\n0,\n1,36893488147419103230\n2,\n";
+        let actual = CostCurve::new(&crabs, &fuel).unwrap().to_csv();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_draw_flat_cost_curve_chart() {
+        let crabs = vec![0, 2];
+        let expected = ["2 |", "2 |o**", "  +---", "   0 2"]
+            .map(|line| line.to_string() + "\n")
+            .concat();
+        let actual = CostCurve::new(&crabs, &Linear).unwrap().to_chart(10, 2);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_draw_cost_curve_chart() {
+        let crabs = vec![0, 4];
+        let expected = [
+            "10 |*   *",
+            "   | |  |",
+            "   | |  |",
+            "   | * *",
+            " 6 |  o",
+            "   +-----",
+            "    0   4",
+        ]
+        .map(|line| line.to_string() + "\n")
+        .concat();
+        let actual = CostCurve::new(&crabs, &Triangular).unwrap().to_chart(10, 5);
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,116 @@
+/// A model for the fuel a crab needs to move a distance.
+pub trait FuelCost {
+    /// The fuel needed to move the given distance.
+    ///
+    /// Returns `None` if the cost does not fit into 128 bits.
+    fn cost(&self, distance: u64) -> Option<u128>;
+}
+
+/// Each step costs one fuel.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Linear;
+
+impl FuelCost for Linear {
+    fn cost(&self, distance: u64) -> Option<u128> {
+        Some(distance as u128)
+    }
+}
+
+/// Each step costs one fuel more than the step before.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Triangular;
+
+impl FuelCost for Triangular {
+    fn cost(&self, distance: u64) -> Option<u128> {
+        // Sum of all integers in that range, which always fits into 128 bits
+        let distance = distance as u128;
+
+        Some(distance * (distance + 1) / 2)
+    }
+}
+
+/// The cost is the squared distance.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Quadratic;
+
+impl FuelCost for Quadratic {
+    fn cost(&self, distance: u64) -> Option<u128> {
+        // Always fits into 128 bits
+        Some(distance as u128 * distance as u128)
+    }
+}
+
+/// The cost is a polynomial of the distance.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Polynomial {
+    /// The coefficients, starting with the constant term.
+    coefficients: Vec<u64>,
+}
+
+impl Polynomial {
+    /// Create a new polynomial from its coefficients, starting with the constant term.
+    ///
+    /// For example, `[1, 0, 2]` describes `1 + 2 * d^2`.
+    pub fn new(coefficients: Vec<u64>) -> Polynomial {
+        Polynomial { coefficients }
+    }
+}
+
+impl FuelCost for Polynomial {
+    fn cost(&self, distance: u64) -> Option<u128> {
+        // Horner's method
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(0u128, |cost, &coefficient| {
+                cost.checked_mul(distance as u128)?
+                    .checked_add(coefficient as u128)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuel::{FuelCost, Linear, Polynomial, Quadratic, Triangular};
+
+    #[test]
+    fn should_calculate_linear_cost() {
+        assert_eq!(Linear.cost(0), Some(0));
+        assert_eq!(Linear.cost(11), Some(11));
+        assert_eq!(Linear.cost(u64::MAX), Some(u64::MAX as u128));
+    }
+
+    #[test]
+    fn should_calculate_triangular_cost() {
+        assert_eq!(Triangular.cost(0), Some(0));
+        assert_eq!(Triangular.cost(11), Some(66));
+        assert_eq!(Triangular.cost(12), Some(78));
+        assert_eq!(Triangular.cost(u32::MAX as u64), Some(9223372034707292160));
+        assert_eq!(
+            Triangular.cost(u64::MAX),
+            Some(170141183460469231722463931679029329920)
+        );
+    }
+
+    #[test]
+    fn should_calculate_quadratic_cost() {
+        assert_eq!(Quadratic.cost(0), Some(0));
+        assert_eq!(Quadratic.cost(11), Some(121));
+        assert_eq!(Quadratic.cost(1 << 32), Some(1 << 64));
+        assert_eq!(
+            Quadratic.cost(u64::MAX),
+            Some((u64::MAX as u128 - 1) * (u64::MAX as u128 + 1) + 1)
+        );
+    }
+
+    #[test]
+    fn should_calculate_polynomial_cost() {
+        let polynomial = Polynomial::new(vec![1, 0, 2]);
+
+        assert_eq!(polynomial.cost(0), Some(1));
+        assert_eq!(polynomial.cost(3), Some(19));
+        assert_eq!(polynomial.cost(1 << 32), Some((1 << 65) + 1));
+        assert_eq!(polynomial.cost(u64::MAX), None);
+        assert_eq!(Polynomial::new(vec![]).cost(3), Some(0));
+    }
+}
@@ -1,74 +1,422 @@
-use std::fs;
+use std::{env, fs};
+
+use curve::CostCurve;
+use fuel::{FuelCost, Linear, Polynomial, Quadratic, Triangular};
+use rally::determine_rally_points;
+
+mod curve;
+mod fuel;
+mod rally;
+
+/// A crab position that could not be parsed.
+#[derive(Debug, PartialEq)]
+struct ParseError(String);
+
+/// The number of rally points the crabs gather at in the last part.
+const RALLY_POINT_COUNT: usize = 3;
+
+#[derive(Debug, PartialEq)]
+enum AlignmentError {
+    /// There are no crabs to align.
+    NoCrabs,
+    /// The crabs cannot gather without any rally points.
+    NoRallyPoints,
+    /// The total fuel cost does not fit into 128 bits.
+    Overflow,
+}
 
 fn main() {
     let filename = "./input/input.txt";
     let input = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
-    let crabs = parse_input(input);
-    let (optimal_pos, fuel_cost) = determine_optimal_position(&crabs);
+    let crabs = parse_input(input).expect("Something went wrong parsing the input");
+
+    let (optimal_pos, fuel_cost) =
+        median_position(&crabs).expect("Something went wrong aligning the crabs");
+    println!("Part 1: The optimal position is {optimal_pos} with a cost of {fuel_cost} fuel.");
+
+    let (optimal_pos, fuel_cost) =
+        mean_position(&crabs).expect("Something went wrong aligning the crabs");
+    println!("Part 2: The optimal position is {optimal_pos} with a cost of {fuel_cost} fuel.");
 
-    println!("The optimal position is {optimal_pos} with a cost of {fuel_cost} fuel.");
+    // Optionally use another fuel cost model than the one of part 2
+    let fuel = match env::args().nth(2) {
+        Some(model) => parse_fuel_cost(&model).expect("Something went wrong parsing the fuel cost"),
+        None => Box::new(Triangular),
+    };
+
+    let plan = determine_rally_points(&crabs, RALLY_POINT_COUNT, fuel.as_ref())
+        .expect("Something went wrong gathering the crabs");
+    println!(
+        "With {} rally points at {:?}, the crabs need {} fuel.",
+        RALLY_POINT_COUNT, plan.rally_points, plan.fuel_cost
+    );
+
+    // Optionally export the cost curve
+    if let Some(path) = env::args().nth(1) {
+        let curve =
+            CostCurve::new(&crabs, fuel.as_ref()).expect("Something went wrong aligning the crabs");
+
+        fs::write(&path, curve.to_csv()).expect("Something went wrong writing the cost curve");
+        println!("Cost curve written to {}", path);
+        print!("{}", curve.to_chart(80, 20));
+    }
+}
+
+/// Parse a fuel cost model.
+///
+/// Either the name of a model, like `triangular`, or the coefficients of a polynomial,
+/// like `1,0,2`.
+fn parse_fuel_cost(input: &str) -> Result<Box<dyn FuelCost>, ParseError> {
+    match input.trim() {
+        "linear" => Ok(Box::new(Linear)),
+        "triangular" => Ok(Box::new(Triangular)),
+        "quadratic" => Ok(Box::new(Quadratic)),
+        coefficients => coefficients
+            .split(',')
+            .map(|coefficient| {
+                coefficient
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| ParseError(input.to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(|coefficients| Box::new(Polynomial::new(coefficients)) as Box<dyn FuelCost>),
+    }
 }
 
 /// Parse the list of crab positions
-fn parse_input(input: String) -> Vec<i32> {
+fn parse_input(input: String) -> Result<Vec<i64>, ParseError> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Ok(vec![]);
+    }
+
     input
-        .trim()
-        .split(",")
-        .into_iter()
-        .map(|value_str| value_str.parse::<i32>())
-        .filter(|res| res.is_ok())
-        .map(|res| res.unwrap())
+        .split(',')
+        .map(|value_str| {
+            value_str
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| ParseError(value_str.to_string()))
+        })
         .collect()
 }
 
-fn determine_optimal_position(crabs: &Vec<i32>) -> (i32, i32) {
-    let min = crabs.into_iter().min().unwrap();
-    let max = crabs.into_iter().max().unwrap();
-
-    let mut optimum = *min;
-    let mut fuel_cost = i32::MAX;
-
-    for pos in *min..(*max + 1) {
-        let cur_fuel_cost: i32 = crabs
-            .into_iter()
-            .map(|crab_pos| {
-                let distance = (crab_pos - pos).abs();
-                // Sum of all integers in that range
-                let cost = (distance * (distance + 1)) / 2;
-                cost
-            })
-            .sum();
+/// The lowest and the highest crab position.
+fn bounds(crabs: &[i64]) -> Result<(i64, i64), AlignmentError> {
+    let min = crabs.iter().min().ok_or(AlignmentError::NoCrabs)?;
+    let max = crabs.iter().max().ok_or(AlignmentError::NoCrabs)?;
+
+    Ok((*min, *max))
+}
 
-        if cur_fuel_cost < fuel_cost {
-            optimum = pos;
-            fuel_cost = cur_fuel_cost;
+/// The total fuel cost for all crabs to move to the given position.
+///
+/// Returns `None` if the cost overflows.
+fn total_fuel_cost<F: FuelCost + ?Sized>(crabs: &[i64], fuel: &F, pos: i64) -> Option<u128> {
+    crabs.iter().try_fold(0u128, |total, crab_pos| {
+        total.checked_add(fuel.cost(crab_pos.abs_diff(pos))?)
+    })
+}
+
+/// Determine the cheapest of the given positions.
+///
+/// On ties, the lowest position wins.
+/// Positions where the total cost does not fit into 128 bits are skipped.
+fn cheapest_position<F: FuelCost + ?Sized>(
+    crabs: &[i64],
+    fuel: &F,
+    positions: impl Iterator<Item = i64>,
+) -> Result<(i64, u128), AlignmentError> {
+    let mut optimum = None;
+
+    for pos in positions {
+        if let Some(cur_fuel_cost) = total_fuel_cost(crabs, fuel, pos) {
+            match optimum {
+                Some((_, fuel_cost)) if fuel_cost <= cur_fuel_cost => {}
+                _ => optimum = Some((pos, cur_fuel_cost)),
+            }
         }
     }
 
-    (optimum, fuel_cost)
+    optimum.ok_or(AlignmentError::Overflow)
+}
+
+/// Determine the optimal position for the linear fuel cost.
+///
+/// Any position between the two middle crabs is optimal, the lower median is returned.
+fn median_position(crabs: &[i64]) -> Result<(i64, u128), AlignmentError> {
+    if crabs.is_empty() {
+        return Err(AlignmentError::NoCrabs);
+    }
+
+    let mut crabs_copy = crabs.to_vec();
+    let (_, &mut median, _) = crabs_copy.select_nth_unstable((crabs.len() - 1) / 2);
+
+    cheapest_position(crabs, &Linear, median..=median)
+}
+
+/// Determine the optimal position for the triangular fuel cost.
+///
+/// The optimum is at most half a step away from the mean position.
+fn mean_position(crabs: &[i64]) -> Result<(i64, u128), AlignmentError> {
+    let (min, max) = bounds(crabs)?;
+    let sum: i128 = crabs.iter().map(|&crab| crab as i128).sum();
+    let mean = sum.div_euclid(crabs.len() as i128) as i64;
+
+    // Also check the neighbors of the rounded mean
+    let candidates = mean.saturating_sub(1).max(min)..=mean.saturating_add(2).min(max);
+    cheapest_position(crabs, &Triangular, candidates)
+}
+
+/// Determine the optimal position for any convex fuel cost with ternary search.
+///
+/// The fuel cost must be convex and must not decrease with the distance,
+/// so that the total cost is convex in the position.
+fn convex_position<F: FuelCost + ?Sized>(
+    crabs: &[i64],
+    fuel: &F,
+) -> Result<(i64, u128), AlignmentError> {
+    let (min, max) = bounds(crabs)?;
+    // The distance between the outermost crabs may not fit into 64 bits
+    let (mut low, mut high) = (min as i128, max as i128);
+
+    while high - low > 2 {
+        let third = (high - low) / 3;
+        let (mid_low, mid_high) = ((low + third) as i64, (high - third) as i64);
+
+        let cost_low = total_fuel_cost(crabs, fuel, mid_low);
+        let cost_high = total_fuel_cost(crabs, fuel, mid_high);
+
+        // Overflowing costs count as infinitely expensive
+        let low_is_cheaper = match (cost_low, cost_high) {
+            (Some(cost_low), Some(cost_high)) => cost_low <= cost_high,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            // Cannot tell in which direction the optimum is
+            (None, None) => return Err(AlignmentError::Overflow),
+        };
+
+        // Keeps the lowest optimal position in range on ties
+        if low_is_cheaper {
+            high = mid_high as i128 - 1;
+        } else {
+            low = mid_low as i128 + 1;
+        }
+    }
+
+    cheapest_position(crabs, fuel, low as i64..=high as i64)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_input, determine_optimal_position};
+    use crate::{
+        bounds, cheapest_position, convex_position,
+        fuel::{FuelCost, Linear, Polynomial, Quadratic, Triangular},
+        mean_position, median_position, parse_fuel_cost, parse_input, AlignmentError, ParseError,
+    };
+
+    /// Determine the position where aligning the crabs costs the least fuel.
+    ///
+    /// Tries every position, so it serves as reference for the fast solvers.
+    /// Returns the position and the total fuel cost.
+    pub(crate) fn determine_optimal_position<F: FuelCost + ?Sized>(
+        crabs: &[i64],
+        fuel: &F,
+    ) -> Result<(i64, u128), AlignmentError> {
+        let (min, max) = bounds(crabs)?;
+
+        cheapest_position(crabs, fuel, min..=max)
+    }
+
+    /// Generate crab positions with a simple linear congruential generator.
+    fn random_crabs(seed: u64, count: usize, spread: i64) -> Vec<i64> {
+        let mut state = seed;
+
+        (0..count)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % spread as u64) as i64
+            })
+            .collect()
+    }
 
     #[test]
     fn should_parse_input() {
         let input = "16,1,2,0,4,2,7,1,2,14\n".to_string();
-        let expected = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let expected = Ok(vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
         let actual = parse_input(input);
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn should_parse_fuel_cost() {
+        assert_eq!(parse_fuel_cost("linear").unwrap().cost(4), Some(4));
+        assert_eq!(parse_fuel_cost("triangular").unwrap().cost(4), Some(10));
+        assert_eq!(parse_fuel_cost("quadratic").unwrap().cost(4), Some(16));
+        assert_eq!(parse_fuel_cost("1, 0, 2").unwrap().cost(4), Some(33));
+        assert!(matches!(
+            parse_fuel_cost("cubic"),
+            Err(ParseError(model)) if model == "cubic"
+        ));
+    }
+
+    #[test]
+    fn should_report_invalid_input() {
+        let input = "16,1,x2,0\n".to_string();
+        let expected = Err(ParseError("x2".to_string()));
+        let actual = parse_input(input);
+
+        assert_eq!(actual, expected);
+        assert_eq!(parse_input("\n".to_string()), Ok(vec![]));
+    }
+
+    #[test]
+    fn should_report_missing_crabs() {
+        let crabs = vec![];
+
+        assert_eq!(
+            determine_optimal_position(&crabs, &Linear),
+            Err(AlignmentError::NoCrabs)
+        );
+        assert_eq!(median_position(&crabs), Err(AlignmentError::NoCrabs));
+        assert_eq!(mean_position(&crabs), Err(AlignmentError::NoCrabs));
+        assert_eq!(
+            convex_position(&crabs, &Quadratic),
+            Err(AlignmentError::NoCrabs)
+        );
+    }
+
+    #[test]
+    fn should_not_overflow_for_wide_spreads() {
+        let crabs = vec![-3_000_000_000, 0, 3_000_000_000];
+        // Two crabs moving 3 billion steps
+        let expected = Ok((0, 9_000_000_003_000_000_000));
+
+        assert_eq!(mean_position(&crabs), expected);
+        assert_eq!(convex_position(&crabs, &Triangular), expected);
+    }
+
+    #[test]
+    fn should_not_overflow_for_full_range() {
+        let crabs = vec![i64::MIN, i64::MAX];
+
+        // Every position in between costs the same
+        assert_eq!(
+            convex_position(&crabs, &Linear),
+            Ok((i64::MIN, u64::MAX as u128))
+        );
+    }
+
+    #[test]
+    fn should_not_skip_positions_where_a_crab_needs_much_fuel() {
+        // The far crab alone needs more than 64 bits of fuel near the optimum
+        let mut crabs = vec![0; 1000];
+        crabs.push(6_100_000_000);
+        let expected = Ok((6_093_906, 18_586_413_592_507_492_465));
+
+        assert_eq!(mean_position(&crabs), expected);
+        assert_eq!(convex_position(&crabs, &Triangular), expected);
+    }
+
+    #[test]
+    fn should_report_overflowing_costs() {
+        let crabs = vec![0, 1 << 40];
+
+        assert_eq!(
+            convex_position(&crabs, &Polynomial::new(vec![0, 0, 0, 0, 1])),
+            Err(AlignmentError::Overflow)
+        );
+    }
+
     #[test]
     fn should_determine_optimal_position() {
         let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
         let (expected_pos, expected_fuel) = (5, 168);
-        let (actual_pos, actual_fuel) = determine_optimal_position(&crabs);
+        let (actual_pos, actual_fuel) = determine_optimal_position(&crabs, &Triangular).unwrap();
+
+        assert_eq!(actual_pos, expected_pos);
+        assert_eq!(actual_fuel, expected_fuel);
+    }
+
+    #[test]
+    fn should_determine_optimal_position_with_linear_cost() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let (expected_pos, expected_fuel) = (2, 37);
+        let (actual_pos, actual_fuel) = determine_optimal_position(&crabs, &Linear).unwrap();
 
         assert_eq!(actual_pos, expected_pos);
         assert_eq!(actual_fuel, expected_fuel);
     }
+
+    #[test]
+    fn should_determine_optimal_position_with_any_cost() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let fuel_costs: Vec<Box<dyn FuelCost>> = vec![
+            Box::new(Quadratic),
+            // Same as the quadratic cost, shifted by one fuel per crab
+            Box::new(Polynomial::new(vec![1, 0, 1])),
+        ];
+        let expected = [Ok((5, 291)), Ok((5, 301))];
+
+        for (fuel_cost, expected) in fuel_costs.iter().zip(expected) {
+            let actual = determine_optimal_position(&crabs, fuel_cost.as_ref());
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn should_determine_median_position() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+        assert_eq!(median_position(&crabs), Ok((2, 37)));
+    }
+
+    #[test]
+    fn should_determine_mean_position() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+        assert_eq!(mean_position(&crabs), Ok((5, 168)));
+    }
+
+    #[test]
+    fn should_determine_convex_position() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+        assert_eq!(convex_position(&crabs, &Linear), Ok((2, 37)));
+        assert_eq!(convex_position(&crabs, &Triangular), Ok((5, 168)));
+        assert_eq!(convex_position(&crabs, &Quadratic), Ok((5, 291)));
+    }
+
+    #[test]
+    fn should_match_brute_force_with_fast_solvers() {
+        let polynomial = Polynomial::new(vec![3, 1, 0, 1]);
+
+        for seed in 0..50 {
+            let crabs = random_crabs(seed, 1 + seed as usize % 20, 1 + seed as i64 * 7);
+
+            assert_eq!(
+                median_position(&crabs),
+                determine_optimal_position(&crabs, &Linear)
+            );
+            assert_eq!(
+                mean_position(&crabs),
+                determine_optimal_position(&crabs, &Triangular)
+            );
+            assert_eq!(
+                convex_position(&crabs, &Quadratic),
+                determine_optimal_position(&crabs, &Quadratic)
+            );
+            assert_eq!(
+                convex_position(&crabs, &polynomial),
+                determine_optimal_position(&crabs, &polynomial)
+            );
+        }
+    }
 }
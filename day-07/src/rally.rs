@@ -0,0 +1,295 @@
+use crate::{convex_position, fuel::FuelCost, AlignmentError};
+
+/// The crabs gathering at several rally points.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RallyPlan {
+    /// The chosen rally points, in ascending order.
+    pub rally_points: Vec<i64>,
+    /// For every crab, the index of the rally point it moves to.
+    pub assignment: Vec<usize>,
+    /// The total fuel cost of all crabs.
+    pub fuel_cost: u128,
+}
+
+/// The cheapest cost of some crabs, and where the last of their groups starts.
+type Split = Option<(u128, usize)>;
+
+/// Determine up to `count` rally points that minimize the total fuel cost.
+///
+/// With a cost that does not decrease with the distance, every crab moves to its nearest
+/// rally point, so the rally points split the sorted crabs into consecutive groups.
+/// The best split is found with dynamic programming over the sorted positions,
+/// solving every group with [`convex_position`], so the fuel cost must be convex.
+///
+/// The start of the last group never moves left when more crabs are added, so divide and
+/// conquer needs `O(n log n)` group solutions per rally point, each a ternary search.
+pub fn determine_rally_points<F: FuelCost + ?Sized>(
+    crabs: &[i64],
+    count: usize,
+    fuel: &F,
+) -> Result<RallyPlan, AlignmentError> {
+    if crabs.is_empty() {
+        return Err(AlignmentError::NoCrabs);
+    }
+    if count == 0 {
+        return Err(AlignmentError::NoRallyPoints);
+    }
+
+    // More rally points than crabs don't help
+    let count = count.min(crabs.len());
+    let n = crabs.len();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&index| crabs[index]);
+    let sorted: Vec<i64> = order.iter().map(|&index| crabs[index]).collect();
+
+    // The optimal rally point of the sorted crabs `start..end`, `None` if it overflows
+    let group = |start: usize, end: usize| convex_position(&sorted[start..end], fuel).ok();
+
+    // The cheapest cost of the first `end` crabs with `g + 1` rally points
+    let mut best: Vec<Vec<Split>> = vec![vec![None; n + 1]; count];
+
+    for (end, split) in best[0].iter_mut().enumerate().skip(1) {
+        *split = group(0, end).map(|(_, cost)| (cost, 0));
+    }
+
+    for g in 1..count {
+        let (before, rest) = best.split_at_mut(g);
+        let bounds = ((g + 1, n), (g, n - 1));
+
+        split_groups(&before[g - 1], &mut rest[0], bounds, &group);
+    }
+
+    let (fuel_cost, _) = best[count - 1][n].ok_or(AlignmentError::Overflow)?;
+
+    // Walk back through the groups to find the rally points
+    let mut rally_points = vec![0; count];
+    let mut assignment = vec![0; n];
+    let mut end = n;
+
+    for g in (0..count).rev() {
+        let (_, start) = best[g][end].unwrap();
+        let (rally_point, _) = group(start, end).unwrap();
+
+        rally_points[g] = rally_point;
+
+        for &index in &order[start..end] {
+            assignment[index] = g;
+        }
+
+        end = start;
+    }
+
+    Ok(RallyPlan {
+        rally_points,
+        assignment,
+        fuel_cost,
+    })
+}
+
+/// Find the cheapest start of the last group for every end in the range.
+///
+/// The bounds are the inclusive ranges of the ends and of the possible starts.
+/// The cheapest start for the middle end splits the starts to consider for the other ends.
+fn split_groups(
+    before: &[Split],
+    current: &mut [Split],
+    ((min_end, max_end), (min_start, max_start)): ((usize, usize), (usize, usize)),
+    group: &impl Fn(usize, usize) -> Option<(i64, u128)>,
+) {
+    if min_end > max_end {
+        return;
+    }
+
+    let end = (min_end + max_end) / 2;
+    let mut cheapest: Split = None;
+
+    for start in min_start..=max_start.min(end - 1) {
+        let candidate = before[start]
+            .zip(group(start, end))
+            .and_then(|((before, _), (_, cost))| before.checked_add(cost));
+
+        if let Some(cost) = candidate {
+            if cheapest.is_none_or(|(cheapest_cost, _)| cost < cheapest_cost) {
+                cheapest = Some((cost, start));
+            }
+        }
+    }
+
+    current[end] = cheapest;
+
+    // Without any start, the other ends cannot be narrowed down
+    let (left_max, right_min) = match cheapest {
+        Some((_, start)) => (start, start),
+        None => (max_start, min_start),
+    };
+
+    if end > min_end {
+        split_groups(
+            before,
+            current,
+            ((min_end, end - 1), (min_start, left_max)),
+            group,
+        );
+    }
+    split_groups(
+        before,
+        current,
+        ((end + 1, max_end), (right_min, max_start)),
+        group,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        convex_position,
+        fuel::{FuelCost, Linear, Quadratic, Triangular},
+        median_position,
+        rally::{determine_rally_points, RallyPlan},
+        AlignmentError,
+    };
+
+    #[test]
+    fn should_determine_rally_points() {
+        let crabs = vec![101, 0, 2, 100, 1, 102];
+        let expected = Ok(RallyPlan {
+            rally_points: vec![1, 101],
+            assignment: vec![1, 0, 0, 1, 0, 1],
+            fuel_cost: 4,
+        });
+        let actual = determine_rally_points(&crabs, 2, &Linear);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_use_single_rally_point_like_single_position() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let plan = determine_rally_points(&crabs, 1, &Linear).unwrap();
+
+        assert_eq!(
+            median_position(&crabs),
+            Ok((plan.rally_points[0], plan.fuel_cost))
+        );
+        assert!(plan.assignment.iter().all(|&index| index == 0));
+    }
+
+    #[test]
+    fn should_not_use_more_rally_points_than_crabs() {
+        let crabs = vec![5, 3];
+        let plan = determine_rally_points(&crabs, 4, &Triangular).unwrap();
+
+        assert_eq!(plan.rally_points, vec![3, 5]);
+        assert_eq!(plan.assignment, vec![1, 0]);
+        assert_eq!(plan.fuel_cost, 0);
+    }
+
+    #[test]
+    fn should_report_invalid_rally_point_counts() {
+        assert_eq!(
+            determine_rally_points(&[], 2, &Linear),
+            Err(AlignmentError::NoCrabs)
+        );
+        assert_eq!(
+            determine_rally_points(&[1, 2], 0, &Linear),
+            Err(AlignmentError::NoRallyPoints)
+        );
+    }
+
+    /// The cheapest split into groups, trying every start of every group.
+    fn reference_fuel_cost(crabs: &[i64], count: usize, fuel: &dyn FuelCost) -> u128 {
+        let mut sorted = crabs.to_vec();
+        sorted.sort_unstable();
+
+        let n = sorted.len();
+        let group =
+            |start: usize, end: usize| convex_position(&sorted[start..end], fuel).unwrap().1;
+        let mut best = vec![vec![u128::MAX; n + 1]; count + 1];
+        best[0][0] = 0;
+
+        for g in 1..(count + 1) {
+            for end in 1..(n + 1) {
+                for start in 0..end {
+                    if best[g - 1][start] != u128::MAX {
+                        let cost = best[g - 1][start] + group(start, end);
+                        best[g][end] = best[g][end].min(cost);
+                    }
+                }
+            }
+        }
+
+        best[count][n]
+    }
+
+    #[test]
+    fn should_match_reference_split() {
+        let costs: [&dyn FuelCost; 3] = [&Linear, &Triangular, &Quadratic];
+        let mut state = 17u64;
+        let crabs: Vec<i64> = (0..60)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) % 500) as i64
+            })
+            .collect();
+
+        for fuel in costs {
+            for count in 1..6 {
+                let plan = determine_rally_points(&crabs, count, fuel).unwrap();
+
+                assert_eq!(plan.fuel_cost, reference_fuel_cost(&crabs, count, fuel));
+            }
+        }
+    }
+
+    #[test]
+    fn should_split_many_crabs() {
+        let crabs: Vec<i64> = (0..400).map(|crab| crab * crab % 1999).collect();
+        let plan = determine_rally_points(&crabs, 3, &Triangular).unwrap();
+
+        assert_eq!(plan.rally_points.len(), 3);
+        // Every crab moves to its assigned rally point
+        let fuel_cost: u128 = crabs
+            .iter()
+            .zip(&plan.assignment)
+            .map(|(&crab, &index)| {
+                Triangular
+                    .cost(crab.abs_diff(plan.rally_points[index]))
+                    .unwrap()
+            })
+            .sum();
+
+        assert_eq!(plan.fuel_cost, fuel_cost);
+    }
+
+    #[test]
+    fn should_match_brute_force_with_two_rally_points() {
+        let crabs = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+        let costs: [&dyn FuelCost; 3] = [&Linear, &Triangular, &Quadratic];
+
+        for fuel in costs {
+            let mut expected = u128::MAX;
+
+            for first in 0..17 {
+                for second in first..17 {
+                    let cost: u128 = crabs
+                        .iter()
+                        .map(|&crab: &i64| {
+                            let to_first = fuel.cost(crab.abs_diff(first)).unwrap();
+                            let to_second = fuel.cost(crab.abs_diff(second)).unwrap();
+                            to_first.min(to_second)
+                        })
+                        .sum();
+
+                    expected = expected.min(cost);
+                }
+            }
+
+            let plan = determine_rally_points(&crabs, 2, fuel).unwrap();
+
+            assert_eq!(plan.fuel_cost, expected);
+        }
+    }
+}
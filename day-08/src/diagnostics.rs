@@ -0,0 +1,125 @@
+use crate::{permutation::permutation_mappings, segment::SevenSegment};
+
+/// A problem found in an entry.
+#[derive(Debug, PartialEq)]
+pub enum Issue {
+    /// The entry does not have ten unique patterns.
+    PatternCount(usize),
+    /// The pattern appears several times among the unique patterns.
+    DuplicatePattern(String),
+    /// No wiring turns all unique patterns into digits.
+    NoWiring,
+    /// The given number of wirings turn all unique patterns into digits.
+    AmbiguousWiring(usize),
+    /// The output pattern does not appear among the unique patterns.
+    UnseenOutput(String),
+}
+
+/// Check that an entry can be decoded reliably.
+///
+/// The ten unique patterns must be distinct and explained by exactly one wiring,
+/// and every output pattern must be one of them.
+pub fn check_entry(input_digits: &[SevenSegment], output_digits: &[SevenSegment]) -> Vec<Issue> {
+    let mut issues = vec![];
+
+    if input_digits.len() != 10 {
+        issues.push(Issue::PatternCount(input_digits.len()));
+    }
+
+    for (index, digit) in input_digits.iter().enumerate() {
+        let first = input_digits.iter().position(|other| other == digit);
+        let count = input_digits.iter().filter(|&other| other == digit).count();
+
+        // Only report the first occurrence
+        if count > 1 && first == Some(index) {
+            issues.push(Issue::DuplicatePattern(digit.to_string()));
+        }
+    }
+
+    match permutation_mappings(input_digits).len() {
+        0 => issues.push(Issue::NoWiring),
+        1 => {}
+        count => issues.push(Issue::AmbiguousWiring(count)),
+    }
+
+    for digit in output_digits {
+        if !input_digits.contains(digit) {
+            issues.push(Issue::UnseenOutput(digit.to_string()));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diagnostics::{check_entry, Issue},
+        parse_input_line,
+    };
+
+    fn check_line(line: &str) -> Vec<Issue> {
+        let (input_digits, output_digits) = parse_input_line(line.to_string()).unwrap();
+
+        check_entry(&input_digits, &output_digits)
+    }
+
+    #[test]
+    fn should_accept_consistent_entry() {
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+        assert_eq!(check_line(line), vec![]);
+    }
+
+    #[test]
+    fn should_report_unseen_outputs() {
+        // The output `bcdg` does not show a digit of this wiring
+        let line =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb gbdc cdfeb cdbaf";
+
+        assert_eq!(
+            check_line(line),
+            vec![Issue::UnseenOutput("bcdg".to_string())]
+        );
+    }
+
+    #[test]
+    fn should_report_duplicate_patterns() {
+        // The pattern of 5 is replaced with the one of 3
+        let line =
+            "acedgfb fbcad gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | fbcad fbcad fbcad fbcad";
+
+        assert_eq!(
+            check_line(line),
+            vec![Issue::DuplicatePattern("abcdf".to_string())]
+        );
+    }
+
+    #[test]
+    fn should_report_missing_wiring() {
+        // The pattern of 5 is garbled from `cdfbe` to `cdfbg`
+        let line =
+            "acedgfb cdfbg gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+        assert_eq!(
+            check_line(line),
+            vec![
+                Issue::NoWiring,
+                Issue::UnseenOutput("bcdef".to_string()),
+                Issue::UnseenOutput("bcdef".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_ambiguous_wirings() {
+        // Only the patterns of 1 and 8 are known
+        let line = "ab acedgfb | ab ab";
+
+        assert_eq!(
+            check_line(line),
+            vec![Issue::PatternCount(2), Issue::AmbiguousWiring(240)]
+        );
+    }
+}
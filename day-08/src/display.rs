@@ -0,0 +1,459 @@
+use std::sync::OnceLock;
+
+/// The most segments a display can have, one letter for each.
+const MAX_SEGMENTS: usize = 26;
+
+/// The glyphs of the seven-segment digits.
+const SEVEN_SEGMENT_GLYPHS: [(&str, char); 10] = [
+    ("abcefg", '0'),
+    ("cf", '1'),
+    ("acdeg", '2'),
+    ("acdfg", '3'),
+    ("bcdf", '4'),
+    ("abdfg", '5'),
+    ("abdefg", '6'),
+    ("acf", '7'),
+    ("abcdefg", '8'),
+    ("abcdfg", '9'),
+];
+
+/// A display definition that is not valid.
+#[derive(Debug, PartialEq)]
+pub enum DefinitionError {
+    /// The display needs between 1 and 26 segments.
+    SegmentCount(usize),
+    /// The glyph pattern uses unknown segments.
+    InvalidPattern(String),
+    /// Two glyphs use the same pattern.
+    DuplicatePattern(String),
+}
+
+/// The wiring of a display could not be determined.
+#[derive(Debug, PartialEq)]
+pub enum SolveError {
+    /// The patterns use unknown wires.
+    InvalidPattern,
+    /// No wiring turns all patterns into glyphs.
+    NoWiring,
+    /// Several wirings turn all patterns into glyphs.
+    AmbiguousWiring,
+}
+
+/// A display with any number of segments and the glyphs it can show.
+///
+/// Segments and wires are named with the letters `a`, `b`, ... and patterns are stored
+/// as bit masks, with the first segment in the highest bit.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DisplayDefinition {
+    segment_count: usize,
+    /// The pattern of every glyph and its symbol.
+    glyphs: Vec<(u32, char)>,
+}
+
+/// The connections of the wires to the segments.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Wiring {
+    /// The segment every wire is connected to.
+    segments: Vec<usize>,
+}
+
+/// The state of the solver, narrowing down the wiring.
+#[derive(Debug, Clone)]
+struct Candidates {
+    /// The segments every wire could be connected to.
+    wires: Vec<u32>,
+    /// The glyphs every pattern could show.
+    glyphs: Vec<Vec<usize>>,
+}
+
+impl DisplayDefinition {
+    /// Create a display definition from the segment count and the glyph patterns.
+    pub fn new(
+        segment_count: usize,
+        glyphs: &[(&str, char)],
+    ) -> Result<DisplayDefinition, DefinitionError> {
+        if segment_count == 0 || segment_count > MAX_SEGMENTS {
+            return Err(DefinitionError::SegmentCount(segment_count));
+        }
+
+        let mut definition = DisplayDefinition {
+            segment_count,
+            glyphs: vec![],
+        };
+
+        for &(pattern_str, symbol) in glyphs {
+            let pattern = definition
+                .parse_pattern(pattern_str)
+                .ok_or_else(|| DefinitionError::InvalidPattern(pattern_str.to_string()))?;
+
+            if definition.glyph(pattern).is_some() {
+                return Err(DefinitionError::DuplicatePattern(pattern_str.to_string()));
+            }
+
+            definition.glyphs.push((pattern, symbol));
+        }
+
+        Ok(definition)
+    }
+
+    /// The seven-segment display showing the digits `0` to `9`.
+    ///
+    /// [`SevenSegment`] reads its digit values from this definition as well.
+    ///
+    /// [`SevenSegment`]: crate::segment::SevenSegment
+    pub fn seven_segment() -> &'static DisplayDefinition {
+        static SEVEN_SEGMENT: OnceLock<DisplayDefinition> = OnceLock::new();
+
+        SEVEN_SEGMENT.get_or_init(|| DisplayDefinition::new(7, &SEVEN_SEGMENT_GLYPHS).unwrap())
+    }
+
+    /// The bit of the segment or wire with the given index.
+    fn bit(&self, index: usize) -> u32 {
+        1 << (self.segment_count - 1 - index)
+    }
+
+    /// The bit mask of all segments.
+    fn all_segments(&self) -> u32 {
+        u32::MAX >> (32 - self.segment_count)
+    }
+
+    /// Parse a pattern of segment letters, `None` if it contains unknown segments.
+    pub fn parse_pattern(&self, input: &str) -> Option<u32> {
+        input.chars().try_fold(0, |pattern, letter| {
+            let index = (letter as usize).checked_sub('a' as usize)?;
+
+            if index < self.segment_count {
+                Some(pattern | self.bit(index))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The symbol of the glyph with the given segments.
+    pub fn glyph(&self, pattern: u32) -> Option<char> {
+        self.glyphs
+            .iter()
+            .find(|&&(glyph, _)| glyph == pattern)
+            .map(|&(_, symbol)| symbol)
+    }
+
+    /// The segments of the glyph with the given symbol.
+    pub fn pattern(&self, symbol: char) -> Option<u32> {
+        self.glyphs
+            .iter()
+            .find(|&&(_, glyph_symbol)| glyph_symbol == symbol)
+            .map(|&(pattern, _)| pattern)
+    }
+
+    /// The symbol of the glyph shown by the scrambled wire pattern.
+    pub fn decode(&self, wiring: &Wiring, pattern: u32) -> Option<char> {
+        self.glyph(wiring.remap(pattern))
+    }
+
+    /// Deduce the wiring from the observed wire patterns.
+    ///
+    /// Every pattern narrows down the glyphs it could show and thereby the segments
+    /// its wires could be connected to. This is repeated until nothing changes, and
+    /// only if that does not settle the wiring, the remaining choices are tried out.
+    pub fn solve(&self, patterns: &[u32]) -> Result<Wiring, SolveError> {
+        if patterns
+            .iter()
+            .any(|&pattern| pattern & !self.all_segments() != 0)
+        {
+            return Err(SolveError::InvalidPattern);
+        }
+
+        let mut patterns = patterns.to_vec();
+        patterns.sort_unstable();
+        patterns.dedup();
+
+        let candidates = Candidates {
+            wires: vec![self.all_segments(); self.segment_count],
+            glyphs: patterns
+                .iter()
+                .map(|pattern| {
+                    (0..self.glyphs.len())
+                        .filter(|&index| self.glyphs[index].0.count_ones() == pattern.count_ones())
+                        .collect()
+                })
+                .collect(),
+        };
+
+        let mut solutions = vec![];
+        self.search(&patterns, candidates, &mut solutions);
+
+        match solutions.len() {
+            0 => Err(SolveError::NoWiring),
+            1 => Ok(solutions.pop().unwrap()),
+            _ => Err(SolveError::AmbiguousWiring),
+        }
+    }
+
+    /// Find up to two wirings by propagating the constraints and trying out the rest.
+    fn search(&self, patterns: &[u32], mut candidates: Candidates, solutions: &mut Vec<Wiring>) {
+        if solutions.len() > 1 || !self.propagate(patterns, &mut candidates) {
+            return;
+        }
+
+        // Try the wire with the fewest options
+        let open_wire = (0..self.segment_count)
+            .filter(|&wire| candidates.wires[wire].count_ones() > 1)
+            .min_by_key(|&wire| candidates.wires[wire].count_ones());
+
+        match open_wire {
+            Some(wire) => {
+                for segment in 0..self.segment_count {
+                    if candidates.wires[wire] & self.bit(segment) != 0 {
+                        let mut guess = candidates.clone();
+                        guess.wires[wire] = self.bit(segment);
+                        self.search(patterns, guess, solutions);
+                    }
+                }
+            }
+            None => {
+                let segments = candidates
+                    .wires
+                    .iter()
+                    .map(|&segment| self.segment_count - 1 - segment.trailing_zeros() as usize)
+                    .collect();
+                let wiring = Wiring { segments };
+
+                if patterns
+                    .iter()
+                    .all(|&pattern| self.decode(&wiring, pattern).is_some())
+                {
+                    solutions.push(wiring);
+                }
+            }
+        }
+    }
+
+    /// Narrow down the candidates until nothing changes.
+    ///
+    /// Returns `false` if the candidates contradict each other.
+    fn propagate(&self, patterns: &[u32], candidates: &mut Candidates) -> bool {
+        let all_segments = self.all_segments();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (pattern, glyphs) in patterns.iter().zip(candidates.glyphs.iter_mut()) {
+                // A wire that is on must lead to a segment of the glyph and vice versa
+                let fits = |wire: usize, glyph: u32| {
+                    let segments = if pattern & self.bit(wire) != 0 {
+                        glyph
+                    } else {
+                        !glyph & all_segments
+                    };
+                    candidates.wires[wire] & segments
+                };
+
+                let count = glyphs.len();
+                glyphs.retain(|&index| {
+                    let glyph = self.glyphs[index].0;
+                    (0..self.segment_count).all(|wire| fits(wire, glyph) != 0)
+                });
+                changed |= glyphs.len() != count;
+
+                let allowed: Vec<u32> = (0..self.segment_count)
+                    .map(|wire| {
+                        glyphs.iter().fold(0, |allowed, &index| {
+                            allowed | fits(wire, self.glyphs[index].0)
+                        })
+                    })
+                    .collect();
+
+                for (wire, allowed) in allowed.into_iter().enumerate() {
+                    changed |= candidates.wires[wire] != allowed;
+                    candidates.wires[wire] = allowed;
+                }
+            }
+
+            if candidates.glyphs.iter().any(|glyphs| glyphs.is_empty())
+                || candidates.wires.contains(&0)
+            {
+                return false;
+            }
+
+            // A segment taken by one wire cannot be used by the others
+            for wire in 0..self.segment_count {
+                let segment = candidates.wires[wire];
+
+                if segment.count_ones() == 1 {
+                    for other in 0..self.segment_count {
+                        if other != wire && candidates.wires[other] & segment != 0 {
+                            candidates.wires[other] &= !segment;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            // A segment only one wire can use belongs to that wire
+            for segment in 0..self.segment_count {
+                let bit = self.bit(segment);
+                let mut wires =
+                    (0..self.segment_count).filter(|&wire| candidates.wires[wire] & bit != 0);
+
+                match (wires.next(), wires.next()) {
+                    (None, _) => return false,
+                    (Some(wire), None) if candidates.wires[wire] != bit => {
+                        candidates.wires[wire] = bit;
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            // A glyph shown by one pattern cannot be shown by the others
+            for index in 0..patterns.len() {
+                if let [glyph] = candidates.glyphs[index][..] {
+                    for (other, glyphs) in candidates.glyphs.iter_mut().enumerate() {
+                        if other != index && glyphs.contains(&glyph) {
+                            glyphs.retain(|&candidate| candidate != glyph);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl Wiring {
+    /// Convert a wire pattern to the pattern of the lit segments.
+    pub fn remap(&self, pattern: u32) -> u32 {
+        let last = self.segments.len() - 1;
+
+        self.segments
+            .iter()
+            .enumerate()
+            .filter(|&(wire, _)| pattern & (1 << (last - wire)) != 0)
+            .fold(0, |segments, (_, &segment)| {
+                segments | 1 << (last - segment)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        determine_mapping,
+        display::{DefinitionError, DisplayDefinition, SolveError, Wiring},
+        parse_digit_list,
+    };
+
+    /// Convert the wiring to the mapping format of `determine_mapping`.
+    fn to_mapping(wiring: &Wiring) -> [u8; 7] {
+        let mut mapping = [0; 7];
+
+        for wire in 0..7 {
+            mapping[wiring.segments[wire]] = 1 << (6 - wire);
+        }
+
+        mapping
+    }
+
+    fn parse_patterns(definition: &DisplayDefinition, input: &str) -> Vec<u32> {
+        input
+            .split(' ')
+            .map(|pattern| definition.parse_pattern(pattern).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn should_find_glyphs_and_patterns() {
+        let definition = DisplayDefinition::seven_segment();
+
+        assert_eq!(definition.pattern('5'), Some(0b1101011));
+        assert_eq!(definition.glyph(0b1101011), Some('5'));
+        assert_eq!(definition.pattern('x'), None);
+        assert_eq!(definition.glyph(0b1000001), None);
+
+        for pattern in 0..128u32 {
+            if let Some(symbol) = definition.glyph(pattern) {
+                assert_eq!(definition.pattern(symbol), Some(pattern));
+            }
+        }
+    }
+
+    #[test]
+    fn should_solve_seven_segment_wiring() {
+        let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+        let definition = DisplayDefinition::seven_segment();
+        let wiring = definition
+            .solve(&parse_patterns(definition, input))
+            .unwrap();
+        let expected = determine_mapping(&parse_digit_list(input.to_string()).unwrap()).unwrap();
+
+        assert_eq!(to_mapping(&wiring), expected);
+
+        let output: String = parse_patterns(definition, "cdfeb fcadb cdfeb cdbaf")
+            .into_iter()
+            .map(|pattern| definition.decode(&wiring, pattern).unwrap())
+            .collect();
+
+        assert_eq!(output, "5353");
+    }
+
+    #[test]
+    fn should_solve_partial_seven_segment_wiring() {
+        // The patterns of 1 and 4 are missing, the output digits help out
+        let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb cagedb cdfeb fcadb";
+        let definition = DisplayDefinition::seven_segment();
+        let wiring = definition.solve(&parse_patterns(definition, input));
+
+        assert_eq!(wiring.map(|wiring| wiring.segments[0]), Ok(2));
+    }
+
+    #[test]
+    fn should_solve_custom_display() {
+        // A bar graph with four segments
+        let glyphs = [("a", '1'), ("ab", '2'), ("abc", '3'), ("abcd", '4')];
+        let definition = DisplayDefinition::new(4, &glyphs).unwrap();
+        let patterns = parse_patterns(&definition, "c cb cba cbad");
+        let wiring = definition.solve(&patterns).unwrap();
+
+        assert_eq!(definition.decode(&wiring, patterns[2]), Some('3'));
+        assert_eq!(definition.decode(&wiring, patterns[3]), Some('4'));
+        assert_eq!(wiring.segments, vec![2, 1, 0, 3]);
+    }
+
+    #[test]
+    fn should_report_unsolvable_wirings() {
+        let definition = DisplayDefinition::new(4, &[("ab", 'x'), ("abc", 'y')]).unwrap();
+
+        assert_eq!(
+            definition.solve(&parse_patterns(&definition, "cd")),
+            Err(SolveError::AmbiguousWiring)
+        );
+        assert_eq!(
+            definition.solve(&parse_patterns(&definition, "a")),
+            Err(SolveError::NoWiring)
+        );
+        assert_eq!(
+            definition.solve(&[0b10000]),
+            Err(SolveError::InvalidPattern)
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_definitions() {
+        assert_eq!(
+            DisplayDefinition::new(0, &[]),
+            Err(DefinitionError::SegmentCount(0))
+        );
+        assert_eq!(
+            DisplayDefinition::new(3, &[("abd", '1')]),
+            Err(DefinitionError::InvalidPattern("abd".to_string()))
+        );
+        assert_eq!(
+            DisplayDefinition::new(3, &[("ab", '1'), ("ba", '2')]),
+            Err(DefinitionError::DuplicatePattern("ba".to_string()))
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::segment::SevenSegment;
+
+/// A seeded pseudo random number generator (SplitMix64).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Shuffle the items with the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = (self.next_u64() % (index as u64 + 1)) as usize;
+            items.swap(index, other);
+        }
+    }
+}
+
+/// A generated line of input and the wiring that scrambled it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GeneratedEntry {
+    /// The line in the format of the puzzle input.
+    pub line: String,
+    /// The wiring in the format of `determine_mapping`.
+    pub mapping: [u8; 7],
+}
+
+/// Write a scrambled pattern with its letters in random order.
+fn write_pattern(pattern: SevenSegment, rng: &mut Rng) -> String {
+    let mut letters: Vec<char> = pattern.to_string().chars().collect();
+    rng.shuffle(&mut letters);
+
+    letters.into_iter().collect()
+}
+
+/// Generate a scrambled entry showing the decimal digits of a number, like `0053`.
+///
+/// Picks a random wiring and writes the ten unique patterns in random order,
+/// followed by the scrambled digits, keeping leading zeros.
+/// Returns `None` if the number contains other characters than decimal digits.
+pub fn generate_entry(number: &str, rng: &mut Rng) -> Option<GeneratedEntry> {
+    let mut wires: [usize; 7] = [0, 1, 2, 3, 4, 5, 6];
+    rng.shuffle(&mut wires);
+    let mapping = wires.map(|wire| 1 << (6 - wire));
+
+    let mut values: Vec<u8> = (0..10).collect();
+    rng.shuffle(&mut values);
+
+    let scramble = |value: u8, rng: &mut Rng| {
+        let digit = SevenSegment::from_digit(value).unwrap();
+        write_pattern(digit.scramble(&mapping), rng)
+    };
+
+    let patterns: Vec<String> = values.iter().map(|&value| scramble(value, rng)).collect();
+    let output: Vec<String> = number
+        .chars()
+        .map(|digit| Some(scramble(digit.to_digit(10)? as u8, rng)))
+        .collect::<Option<_>>()?;
+
+    Some(GeneratedEntry {
+        line: format!("{} | {}", patterns.join(" "), output.join(" ")),
+        mapping,
+    })
+}
+
+/// Generate a scrambled entry for every number, reproducible with the seed.
+pub fn generate_entries<S: AsRef<str>>(numbers: &[S], seed: u64) -> Option<Vec<GeneratedEntry>> {
+    let mut rng = Rng::new(seed);
+
+    numbers
+        .iter()
+        .map(|number| generate_entry(number.as_ref(), &mut rng))
+        .collect()
+}
+
+/// Generate a puzzle input with one line for every number.
+pub fn generate_input<S: AsRef<str>>(numbers: &[S], seed: u64) -> Option<String> {
+    let entries = generate_entries(numbers, seed)?;
+
+    Some(entries.into_iter().map(|entry| entry.line + "\n").collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calculate_output_value, decode_output_digits, determine_mapping,
+        diagnostics::check_entry,
+        generator::{generate_entries, generate_input},
+        parse_input, parse_input_line,
+    };
+
+    #[test]
+    fn should_generate_reproducible_input() {
+        let numbers = ["5353", "0", "123456789012"];
+
+        assert_eq!(generate_input(&numbers, 7), generate_input(&numbers, 7));
+        assert_ne!(generate_input(&numbers, 7), generate_input(&numbers, 8));
+        assert_eq!(
+            parse_input(generate_input(&numbers, 7).unwrap())
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn should_not_generate_non_digits() {
+        assert_eq!(generate_input(&["12", "-3"], 7), None);
+        assert_eq!(generate_input(&["1a"], 7), None);
+    }
+
+    #[test]
+    fn should_keep_leading_zeros() {
+        let numbers = ["0053", "007", "0"];
+
+        for (number, entry) in numbers.iter().zip(generate_entries(&numbers, 3).unwrap()) {
+            let (input_digits, output_digits) = parse_input_line(entry.line).unwrap();
+
+            assert_eq!(
+                decode_output_digits(&input_digits, &output_digits),
+                Ok(number.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn should_generate_decodable_entries() {
+        let numbers: Vec<String> = (0..50)
+            .map(|index: u64| (index * 7919 % 100_000).to_string())
+            .collect();
+
+        for (number, entry) in numbers.iter().zip(generate_entries(&numbers, 42).unwrap()) {
+            let (input_digits, output_digits) = parse_input_line(entry.line).unwrap();
+
+            assert_eq!(input_digits.len(), 10);
+            assert_eq!(output_digits.len(), number.len());
+            assert!(check_entry(&input_digits, &output_digits).is_empty());
+            assert_eq!(determine_mapping(&input_digits), Some(entry.mapping));
+            assert_eq!(
+                calculate_output_value(input_digits, output_digits),
+                Ok(number.parse().unwrap())
+            );
+        }
+    }
+}
@@ -1,78 +1,47 @@
-use std::fs;
-
-#[derive(Debug)]
-struct InvalidSegment;
-
-trait Digit {
-    fn from_input(input: String) -> Self;
-    fn from_segment(segment: char) -> Result<Self, InvalidSegment>
-    where
-        Self: Sized;
-    fn segment_count(&self) -> u8;
-    fn remap(&self, map: &[u8; 7]) -> Self;
-    fn value(&self) -> u8;
+use std::{env, fs};
+
+use diagnostics::check_entry;
+use display::{DisplayDefinition, SolveError};
+use generator::generate_input;
+use permutation::{closest_mapping, permutation_mappings};
+use render::{render_entry, Style};
+use segment::{InvalidPattern, SevenSegment};
+
+mod diagnostics;
+mod display;
+mod generator;
+mod permutation;
+mod render;
+mod segment;
+
+/// The ten unique patterns and the output digits of a display.
+type Entry = (Vec<SevenSegment>, Vec<SevenSegment>);
+
+/// The reason an entry could not be read.
+#[derive(Debug, PartialEq)]
+enum DecodeError {
+    /// The line does not separate the unique patterns and the output with ` | `.
+    MissingSeparator,
+    /// The pattern contains characters that are not segments.
+    InvalidPattern(String),
+    /// The pattern does not form a digit with the wiring of the entry.
+    InvalidDigit(String),
+    /// The wiring of the entry could not be determined.
+    UnknownWiring,
+    /// The output value does not fit into 64 bits.
+    Overflow,
 }
 
-impl Digit for u8 {
-    /// Convert a segment string to a digit.
-    fn from_input(input: String) -> Self {
-        input
-            .chars()
-            .map(|seg| u8::from_segment(seg))
-            .filter(|res| res.is_ok())
-            .map(|res| res.unwrap())
-            // Combine the segments to the digit representation
-            .sum()
-    }
-
-    /// Convert the segment to a part of the digit.
-    fn from_segment(segment: char) -> Result<Self, InvalidSegment> {
-        match segment {
-            'a' => Ok(0b1000000),
-            'b' => Ok(0b0100000),
-            'c' => Ok(0b0010000),
-            'd' => Ok(0b0001000),
-            'e' => Ok(0b0000100),
-            'f' => Ok(0b0000010),
-            'g' => Ok(0b0000001),
-            _ => Err(InvalidSegment),
-        }
-    }
-
-    /// Count the number of activated segments
-    fn segment_count(&self) -> u8 {
-        (0..8).into_iter().map(|index| (self >> index) & 1).sum()
-    }
-
-    /// Remap the segments.
-    fn remap(&self, map: &[u8; 7]) -> Self {
-        let mut remapped_digit = 0u8;
-
-        for index in 0..7 {
-            if map[index] & self > 0 {
-                remapped_digit += 1 << (6 - index);
-            }
-        }
-
-        remapped_digit
-    }
+/// An entry that could not be read, with its line number starting at 1.
+#[derive(Debug, PartialEq)]
+struct EntryError {
+    line: usize,
+    error: DecodeError,
+}
 
-    /// The decimal value of the digit.
-    /// Only works after it has been converted to the default format.
-    fn value(&self) -> u8 {
-        match self {
-            0b1110111 => 0,
-            0b0010010 => 1,
-            0b1011101 => 2,
-            0b1011011 => 3,
-            0b0111010 => 4,
-            0b1101011 => 5,
-            0b1101111 => 6,
-            0b1010010 => 7,
-            0b1111111 => 8,
-            0b1111011 => 9,
-            _ => panic!("Invalid digit value {:#07b}", self),
-        }
+impl From<InvalidPattern> for DecodeError {
+    fn from(InvalidPattern(pattern): InvalidPattern) -> Self {
+        DecodeError::InvalidPattern(pattern)
     }
 }
 
@@ -80,19 +49,66 @@ fn main() {
     let filename = "./input/input.txt";
     let input = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
-    let lines = parse_input(input);
+    let lines = parse_input(input).expect("Something went wrong parsing the input");
+
+    // Optionally write the output digits with new random wirings, `generate <seed>`
+    if env::args().nth(1).as_deref() == Some("generate") {
+        let seed = env::args().nth(2).map_or(0, |seed| {
+            seed.parse().expect("Something went wrong parsing the seed")
+        });
+        let numbers: Vec<String> = lines
+            .iter()
+            .map(|(input_digits, output_digits)| {
+                decode_output_digits(input_digits, output_digits)
+                    .expect("Something went wrong decoding the entry")
+            })
+            .collect();
+        let input =
+            generate_input(&numbers, seed).expect("Something went wrong generating the input");
+
+        print!("{}", input);
+        return;
+    }
+
+    // Optionally draw the decoded displays, `small` or `large`
+    let style = env::args().nth(1).map(|style| match style.as_str() {
+        "large" => Style::Large,
+        _ => Style::Small,
+    });
 
     let mut unique_digit_count = 0;
     let mut value_sum = 0;
 
-    for (input_digits, output_digits) in lines {
-        unique_digit_count += (&output_digits)
-            .into_iter()
+    for (index, (input_digits, output_digits)) in lines.into_iter().enumerate() {
+        unique_digit_count += output_digits
+            .iter()
             .map(|digit| digit.segment_count())
             .filter(|count| *count == 2 || *count == 3 || *count == 4 || *count == 7)
             .count();
-        
-        let value = calculate_output_value(input_digits, output_digits);
+
+        // Report entries that may not decode reliably, but still decode them
+        let issues = check_entry(&input_digits, &output_digits);
+
+        if !issues.is_empty() {
+            println!("Issues in the entry on line {}: {:?}", index + 1, issues);
+            println!(
+                "The generic solver reads: {:?}",
+                solve_output_digits(&input_digits, &output_digits)
+            );
+        }
+
+        if let Some(style) = style {
+            if let Some(mapping) = decode_mapping(&input_digits, &output_digits) {
+                println!("{}", render_entry(&output_digits, &mapping, style));
+            }
+        }
+
+        let value = calculate_output_value(input_digits, output_digits)
+            .map_err(|error| EntryError {
+                line: index + 1,
+                error,
+            })
+            .expect("Something went wrong decoding the entry");
         value_sum += value;
     }
 
@@ -105,12 +121,8 @@ fn main() {
 /// Example input:
 ///
 /// `eacfd acdfbe cbdegf fcbaedg`
-fn parse_digit_list(input: String) -> Vec<u8> {
-    input
-        .split(" ")
-        .into_iter()
-        .map(|segments| u8::from_input(segments.to_string()))
-        .collect()
+fn parse_digit_list(input: String) -> Result<Vec<SevenSegment>, InvalidPattern> {
+    input.split(' ').map(SevenSegment::try_from).collect()
 }
 
 /// Parse one line of input to the input and output digits.
@@ -118,126 +130,238 @@ fn parse_digit_list(input: String) -> Vec<u8> {
 /// Example input:
 ///
 /// `badc bd dbeaf cfdbge dfb cfbdea efbag edcfgab dcafe degfca | eacfd acdfbe cbdegf fcbaedg`
-fn parse_input_line(input: String) -> (Vec<u8>, Vec<u8>) {
-    let mut parts = input.trim().split(" | ");
+fn parse_input_line(input: String) -> Result<Entry, DecodeError> {
+    let (input, output) = input
+        .trim()
+        .split_once(" | ")
+        .ok_or(DecodeError::MissingSeparator)?;
 
-    let input = parse_digit_list(parts.next().unwrap().to_string());
-    let output = parse_digit_list(parts.next().unwrap().to_string());
+    let input = parse_digit_list(input.to_string())?;
+    let output = parse_digit_list(output.to_string())?;
 
-    (input, output)
+    Ok((input, output))
 }
 
 /// Parse the input file.
-fn parse_input(input: String) -> Vec<(Vec<u8>, Vec<u8>)> {
+fn parse_input(input: String) -> Result<Vec<Entry>, EntryError> {
     input
         .trim()
-        .split("\n")
-        .into_iter()
-        .map(|line| parse_input_line(line.to_string()))
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            parse_input_line(line.to_string()).map_err(|error| EntryError {
+                line: index + 1,
+                error,
+            })
+        })
         .collect()
 }
 
-fn filter_digits_with_count(digits: &Vec<u8>, count: u8) -> Vec<u8> {
+fn filter_digits_with_count(digits: &[u8], count: u32) -> Vec<u8> {
     digits
-        .into_iter()
-        .filter(|digit| digit.segment_count() == count)
-        .map(|&digit| digit)
+        .iter()
+        .filter(|digit| digit.count_ones() == count)
+        .copied()
         .collect()
 }
 
-fn find_digit_with_count(digits: &Vec<u8>, count: u8) -> u8 {
-    *filter_digits_with_count(digits, count).first().unwrap()
+fn find_digit_with_count(digits: &[u8], count: u32) -> Option<u8> {
+    filter_digits_with_count(digits, count).first().copied()
 }
 
-fn calculate_output_value(input_digits: Vec<u8>, output_digits: Vec<u8>) -> u64 {
-    let mapping = determine_mapping(input_digits);
+/// Decode the output digits of an entry, keeping leading zeros.
+fn decode_output_digits(
+    input_digits: &[SevenSegment],
+    output_digits: &[SevenSegment],
+) -> Result<String, DecodeError> {
+    let mapping = decode_mapping(input_digits, output_digits).ok_or(DecodeError::UnknownWiring)?;
+
+    output_digits
+        .iter()
+        .map(|original_digit| {
+            original_digit
+                .remap(&mapping)
+                .value()
+                .map(|value| char::from(b'0' + value))
+                .ok_or_else(|| DecodeError::InvalidDigit(original_digit.to_string()))
+        })
+        .collect()
+}
+
+/// Decode the output digits of an entry with the generic display solver.
+///
+/// Output patterns that don't show a digit with the deduced wiring are read as `?`.
+fn solve_output_digits(
+    input_digits: &[SevenSegment],
+    output_digits: &[SevenSegment],
+) -> Result<String, SolveError> {
+    let definition = DisplayDefinition::seven_segment();
+    let patterns: Vec<u32> = input_digits
+        .iter()
+        .chain(output_digits)
+        .map(|digit| digit.bits() as u32)
+        .collect();
+    let wiring = definition.solve(&patterns)?;
+
+    Ok(output_digits
+        .iter()
+        .map(|digit| {
+            definition
+                .decode(&wiring, digit.bits() as u32)
+                .unwrap_or('?')
+        })
+        .collect())
+}
 
-    let mut value = 0;
+/// Decode the output value of an entry with any number of digits.
+fn calculate_output_value(
+    input_digits: Vec<SevenSegment>,
+    output_digits: Vec<SevenSegment>,
+) -> Result<u64, DecodeError> {
+    decode_output_digits(&input_digits, &output_digits)?
+        .chars()
+        .try_fold(0u64, |value, digit| {
+            value
+                .checked_mul(10)?
+                .checked_add(digit.to_digit(10)? as u64)
+        })
+        .ok_or(DecodeError::Overflow)
+}
 
-    for i in 0..4 {
-        let original_digit = output_digits[i];
-        let remapped_digit = original_digit.remap(&mapping);
-        let digit_value = remapped_digit.value() as u64;
-        value += digit_value * 10u64.pow((3 - i).try_into().unwrap());
+/// Determine the mapping of the segments of an entry.
+///
+/// Falls back to trying all wire permutations if the set operations don't apply,
+/// like for partial or noisy entries. If no wiring explains all patterns, the one
+/// explaining the most is used. Returns `None` if several wirings explain all patterns,
+/// or if no wiring does and several explain the most.
+fn decode_mapping(
+    input_digits: &[SevenSegment],
+    output_digits: &[SevenSegment],
+) -> Option<[u8; 7]> {
+    if let Some(mapping) = determine_mapping(input_digits) {
+        return Some(mapping);
     }
 
-    value
+    // The output digits narrow down the possible wirings as well
+    let patterns = [input_digits, output_digits].concat();
+    let mut mappings = permutation_mappings(&patterns);
+
+    match mappings.len() {
+        0 => closest_mapping(&patterns),
+        1 => mappings.pop(),
+        _ => None,
+    }
 }
 
-/// Determine the mapping of the segments.
-fn determine_mapping(digits: Vec<u8>) -> [u8; 7] {
+/// Determine the mapping of the segments with set operations on the unique patterns.
+///
+/// Returns `None` if a pattern needed for the deduction is missing, or if the result
+/// does not turn every pattern into a digit, like for noisy patterns.
+fn determine_mapping(digits: &[SevenSegment]) -> Option<[u8; 7]> {
+    let patterns = digits;
+    let digits: Vec<u8> = digits.iter().map(|digit| digit.bits()).collect();
+
     // Unique numbers
     // One
-    let cf = find_digit_with_count(&digits, 2);
+    let cf = find_digit_with_count(&digits, 2)?;
     // Four
-    let bcdf = find_digit_with_count(&digits, 4);
+    let bcdf = find_digit_with_count(&digits, 4)?;
     // Seven
-    let acf = find_digit_with_count(&digits, 3);
+    let acf = find_digit_with_count(&digits, 3)?;
     // Eight
-    let abcdefg = find_digit_with_count(&digits, 7);
+    let abcdefg = find_digit_with_count(&digits, 7)?;
 
-    let a = acf - cf;
-    let bd = bcdf - cf;
+    let a = acf.checked_sub(cf)?;
+    let bd = bcdf.checked_sub(cf)?;
 
     // Common segments of digits with five segments
     let adg = filter_digits_with_count(&digits, 5)
         .into_iter()
-        .reduce(|a, b| a & b)
-        .unwrap();
+        .reduce(|a, b| a & b)?;
 
     // Common segments of digits with six segments
     let abfg = filter_digits_with_count(&digits, 6)
         .into_iter()
-        .reduce(|a, b| a & b)
-        .unwrap();
+        .reduce(|a, b| a & b)?;
 
     let bdf = adg ^ abfg;
-    let f = bdf - bd;
+    let f = bdf.checked_sub(bd)?;
 
-    let c = cf - f;
+    let c = cf.checked_sub(f)?;
 
     // Two
     let acdeg = filter_digits_with_count(&digits, 5)
         .into_iter()
-        .filter(|&digit| digit & f == 0)
-        .next()
-        .unwrap();
+        .find(|&digit| digit & f == 0)?;
 
-    let b = abcdefg - acdeg - f;
-    let d = bcdf - cf - b;
+    let b = abcdefg.checked_sub(acdeg)?.checked_sub(f)?;
+    let d = bd.checked_sub(b)?;
 
-    let g = adg - a - d;
-    let e = abcdefg - a - b - c - d - f - g;
+    let g = adg.checked_sub(a)?.checked_sub(d)?;
+    let e = [a, b, c, d, f, g]
+        .iter()
+        .try_fold(abcdefg, |rest, &segment| rest.checked_sub(segment))?;
 
-    [a, b, c, d, e, f, g]
+    let mapping = [a, b, c, d, e, f, g];
+
+    // Noisy patterns lead to segments that are not single distinct wires
+    let is_wiring = mapping.iter().all(|segment| segment.count_ones() == 1)
+        && mapping.iter().fold(0, |wires, segment| wires | segment) == 0b1111111;
+    let is_valid = patterns
+        .iter()
+        .all(|pattern| pattern.remap(&mapping).value().is_some());
+
+    (is_wiring && is_valid).then_some(mapping)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{calculate_output_value, determine_mapping, parse_digit_list, Digit, parse_input_line};
+    use crate::{
+        calculate_output_value, decode_mapping, decode_output_digits, determine_mapping,
+        display::SolveError, parse_digit_list, parse_input, parse_input_line,
+        segment::SevenSegment, solve_output_digits, DecodeError, EntryError,
+    };
 
     #[test]
-    fn should_count_active_segments() {
-        let digit: u8 = 0b1011011;
-        let actual = digit.segment_count();
+    fn should_parse_digit_list() {
+        let input = "eacfd acdfbe cbdegf fcbaedg".to_string();
+        let expected = Ok(vec![
+            SevenSegment::new(0b1011110),
+            SevenSegment::new(0b1111110),
+            SevenSegment::new(0b0111111),
+            SevenSegment::new(0b1111111),
+        ]);
+        let actual = parse_digit_list(input);
 
-        assert_eq!(actual, 5);
+        assert_eq!(actual, expected);
     }
 
     #[test]
-    fn should_parse_digit_input() {
-        let input = "abdfg".to_string();
-        let expected: u8 = 0b1101011;
-        let actual = u8::from_input(input);
+    fn should_report_invalid_patterns_with_line() {
+        let input = "\
+acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf
+acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcaxb cdfeb cdbaf"
+            .to_string();
+        let expected = Err(EntryError {
+            line: 2,
+            error: DecodeError::InvalidPattern("fcaxb".to_string()),
+        });
+        let actual = parse_input(input);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
-    fn should_parse_digit_list() {
-        let input = "eacfd acdfbe cbdegf fcbaedg".to_string();
-        let expected: Vec<u8> = vec![0b1011110, 0b1111110, 0b0111111, 0b1111111];
-        let actual = parse_digit_list(input);
+    fn should_report_missing_separator() {
+        let input = "\
+acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf
+acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab cdfeb fcadb cdfeb cdbaf"
+            .to_string();
+        let expected = Err(EntryError {
+            line: 2,
+            error: DecodeError::MissingSeparator,
+        });
+        let actual = parse_input(input);
 
         assert_eq!(actual, expected);
     }
@@ -245,38 +369,164 @@ mod tests {
     #[test]
     fn should_determine_mapping() {
         let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab".to_string();
-        let input_digits = parse_digit_list(input);
+        let input_digits = parse_digit_list(input).unwrap();
         let expected = [
             0b0001000, 0b0000100, 0b1000000, 0b0000010, 0b0000001, 0b0100000, 0b0010000,
         ];
-        let actual = determine_mapping(input_digits);
+        let actual = determine_mapping(&input_digits);
+
+        assert_eq!(actual, Some(expected));
+    }
+
+    #[test]
+    fn should_not_determine_mapping_from_partial_patterns() {
+        // The patterns with five and six segments are missing
+        let input = parse_digit_list("ab abd abef abcdefg".to_string()).unwrap();
+
+        assert_eq!(determine_mapping(&input), None);
+    }
+
+    #[test]
+    fn should_not_determine_mapping_from_noisy_patterns() {
+        // The pattern of 5 is garbled from `cdfbe` to `cdfbg`
+        let input = "acedgfb cdfbg gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab".to_string();
+
+        assert_eq!(determine_mapping(&parse_digit_list(input).unwrap()), None);
+    }
+
+    #[test]
+    fn should_calculate_output_value() {
+        let input =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
+                .to_string();
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+        let expected = Ok(5353u64);
+        let actual = calculate_output_value(input_digits, output_digits);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
-    fn should_remap_value() {
-        let input = 0b1101010;
-        // cgafedb -> abcdefg
-        let mapping = [
-            0b0010000, 0b0000001, 0b1000000, 0b0000010, 0b0000100, 0b0001000, 0b0100000,
+    fn should_keep_leading_zeros() {
+        let input =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cagedb cagedb cdfbe fbcad"
+                .to_string();
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+
+        assert_eq!(
+            decode_output_digits(&input_digits, &output_digits),
+            Ok("0053".to_string())
+        );
+        assert_eq!(calculate_output_value(input_digits, output_digits), Ok(53));
+    }
+
+    #[test]
+    fn should_calculate_output_value_of_any_length() {
+        let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+        let output_lengths = [
+            (1, Ok(8)),
+            (12, Ok(888_888_888_888)),
+            (20, Err(DecodeError::Overflow)),
         ];
-        let expected = 0b0011011;
 
-        let actual = input.remap(&mapping);
+        for (length, expected) in output_lengths {
+            let output = vec!["acedgfb"; length].join(" ");
+            let (input_digits, output_digits) =
+                parse_input_line(format!("{} | {}", input, output)).unwrap();
+            let actual = calculate_output_value(input_digits, output_digits);
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn should_report_invalid_output_digit() {
+        let input =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fc cdfeb cdbaf"
+                .to_string();
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+        let expected = Err(DecodeError::InvalidDigit("cf".to_string()));
+        let actual = calculate_output_value(input_digits, output_digits);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
-    fn should_calculate_output_value() {
+    fn should_decode_mapping_without_unique_digits() {
+        // The patterns of 1 and 4 are missing
+        let input = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb cagedb".to_string();
+        let output = "cdfeb fcadb cdfeb cdbaf".to_string();
+        let expected = Some([
+            0b0001000, 0b0000100, 0b1000000, 0b0000010, 0b0000001, 0b0100000, 0b0010000,
+        ]);
+        let actual = decode_mapping(
+            &parse_digit_list(input).unwrap(),
+            &parse_digit_list(output).unwrap(),
+        );
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_report_unknown_wiring_of_partial_entry() {
+        let (input_digits, output_digits) =
+            parse_input_line("ab abd abef abcdefg | ab".to_string()).unwrap();
+        let expected = Err(DecodeError::UnknownWiring);
+        let actual = calculate_output_value(input_digits, output_digits);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_decode_noisy_entry_with_closest_mapping() {
+        // The pattern of 5 is garbled from `cdfbe` to `cdfbg`
         let input =
-            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
+            "acedgfb cdfbg gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
                 .to_string();
-        let (input_digits, output_digits) = parse_input_line(input);
-        let expected = 5353u64;
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+        let expected = Ok(5353u64);
         let actual = calculate_output_value(input_digits, output_digits);
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn should_report_unknown_wiring_of_ambiguous_noisy_entry() {
+        // `abcde` could be a 2, 3 or 5 with the wirings explaining the most patterns
+        let input = "a abcde | abcde".to_string();
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+
+        assert_eq!(
+            calculate_output_value(input_digits, output_digits),
+            Err(DecodeError::UnknownWiring)
+        );
+    }
+
+    #[test]
+    fn should_not_decode_ambiguous_mapping() {
+        // Only the pattern of 8 is known, which fits every wiring
+        let input = parse_digit_list("acedgfb".to_string()).unwrap();
+
+        assert_eq!(decode_mapping(&input, &input), None);
+    }
+
+    #[test]
+    fn should_solve_output_digits() {
+        let input =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
+                .to_string();
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+
+        assert_eq!(
+            solve_output_digits(&input_digits, &output_digits),
+            Ok("5353".to_string())
+        );
+
+        let input = parse_digit_list("acedgfb".to_string()).unwrap();
+
+        assert_eq!(
+            solve_output_digits(&input, &input),
+            Err(SolveError::AmbiguousWiring)
+        );
+    }
 }
@@ -0,0 +1,139 @@
+use crate::segment::SevenSegment;
+
+/// All orders of the seven wires, in lexicographic order.
+fn wire_permutations() -> Vec<[usize; 7]> {
+    let mut permutation = [0, 1, 2, 3, 4, 5, 6];
+    let mut permutations = vec![permutation];
+
+    // Step to the next permutation until the wires are in descending order
+    while let Some(pivot) = (0..6).rev().find(|&i| permutation[i] < permutation[i + 1]) {
+        let successor = (pivot + 1..7)
+            .rev()
+            .find(|&i| permutation[i] > permutation[pivot])
+            .unwrap();
+
+        permutation.swap(pivot, successor);
+        permutation[pivot + 1..].reverse();
+        permutations.push(permutation);
+    }
+
+    permutations
+}
+
+/// The segment mapping of a wire permutation, in the format of `determine_mapping`.
+///
+/// Segment `i` is connected to wire `permutation[i]`.
+fn permutation_mapping(permutation: &[usize; 7]) -> [u8; 7] {
+    permutation.map(|wire| 1 << (6 - wire))
+}
+
+/// The number of patterns that form a valid digit with the given mapping.
+fn valid_digit_count(patterns: &[SevenSegment], mapping: &[u8; 7]) -> usize {
+    patterns
+        .iter()
+        .filter(|pattern| pattern.remap(mapping).value().is_some())
+        .count()
+}
+
+/// Determine every mapping that turns all patterns into valid digits.
+///
+/// Tries all 5040 wire permutations, so it also works for entries missing some digits.
+/// Ten distinct patterns always result in exactly one mapping.
+pub fn permutation_mappings(patterns: &[SevenSegment]) -> Vec<[u8; 7]> {
+    wire_permutations()
+        .iter()
+        .map(permutation_mapping)
+        .filter(|mapping| valid_digit_count(patterns, mapping) == patterns.len())
+        .collect()
+}
+
+/// Determine the mapping that turns the most patterns into valid digits.
+///
+/// Meant for noisy entries where some patterns are garbled.
+/// Returns `None` if several mappings turn the most patterns into valid digits.
+pub fn closest_mapping(patterns: &[SevenSegment]) -> Option<[u8; 7]> {
+    let mut best_count = 0;
+    let mut best_mappings = vec![];
+
+    for mapping in wire_permutations().iter().map(permutation_mapping) {
+        let count = valid_digit_count(patterns, &mapping);
+
+        if count > best_count {
+            best_count = count;
+            best_mappings.clear();
+        }
+        if count == best_count {
+            best_mappings.push(mapping);
+        }
+    }
+
+    match best_mappings.as_slice() {
+        &[mapping] => Some(mapping),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        determine_mapping, parse_digit_list,
+        permutation::{closest_mapping, permutation_mappings, wire_permutations},
+    };
+
+    const EXAMPLE: &str = "\
+be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc";
+
+    #[test]
+    fn should_generate_all_wire_permutations() {
+        let permutations = wire_permutations();
+        let mut sorted = permutations.clone();
+        sorted.sort();
+        sorted.dedup();
+
+        assert_eq!(permutations.len(), 5040);
+        assert_eq!(sorted, permutations);
+        assert_eq!(permutations[5039], [6, 5, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn should_match_determined_mapping() {
+        for line in EXAMPLE.lines() {
+            let digits = parse_digit_list(line.to_string()).unwrap();
+            let expected = vec![determine_mapping(&digits).unwrap()];
+            let actual = permutation_mappings(&digits);
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn should_find_closest_mapping_for_noisy_patterns() {
+        // The pattern of 5 is garbled from `cdfbe` to `cdfbg`
+        let input = "acedgfb cdfbg gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab".to_string();
+        let digits = parse_digit_list(input).unwrap();
+        let expected = [
+            0b0001000, 0b0000100, 0b1000000, 0b0000010, 0b0000001, 0b0100000, 0b0010000,
+        ];
+
+        assert!(permutation_mappings(&digits).is_empty());
+        assert_eq!(closest_mapping(&digits), Some(expected));
+    }
+
+    #[test]
+    fn should_not_find_closest_mapping_on_ties() {
+        // `a` is never a digit, and many wirings turn `abcde` into a 2, 3 or 5
+        let digits = parse_digit_list("a abcde".to_string()).unwrap();
+
+        assert!(permutation_mappings(&digits).is_empty());
+        assert_eq!(closest_mapping(&digits), None);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::segment::SevenSegment;
+
+/// The size of the rendered digits.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Style {
+    /// Three rows, drawn with `_` and `|`.
+    Small,
+    /// Seven rows, drawn with the segment letters like in the puzzle description.
+    Large,
+}
+
+impl Style {
+    /// The number of rows of a digit.
+    fn height(&self) -> usize {
+        match self {
+            Style::Small => 3,
+            Style::Large => 7,
+        }
+    }
+}
+
+/// Draw a single pattern as the given rows.
+fn render_pattern(pattern: &SevenSegment, style: Style) -> Vec<String> {
+    // The character of a segment, depending on whether it is lit
+    let segment = |letter: char, lit: char, unlit: char| {
+        if pattern.has_segment(letter) {
+            lit
+        } else {
+            unlit
+        }
+    };
+
+    match style {
+        Style::Small => vec![
+            format!(" {} ", segment('a', '_', ' ')),
+            format!(
+                "{}{}{}",
+                segment('b', '|', ' '),
+                segment('d', '_', ' '),
+                segment('c', '|', ' ')
+            ),
+            format!(
+                "{}{}{}",
+                segment('e', '|', ' '),
+                segment('g', '_', ' '),
+                segment('f', '|', ' ')
+            ),
+        ],
+        Style::Large => {
+            let horizontal =
+                |letter| format!(" {} ", segment(letter, letter, '.').to_string().repeat(4));
+            let vertical = |left, right| {
+                format!(
+                    "{}    {}",
+                    segment(left, left, '.'),
+                    segment(right, right, '.')
+                )
+            };
+
+            vec![
+                horizontal('a'),
+                vertical('b', 'c'),
+                vertical('b', 'c'),
+                horizontal('d'),
+                vertical('e', 'f'),
+                vertical('e', 'f'),
+                horizontal('g'),
+            ]
+        }
+    }
+}
+
+/// Draw the patterns next to each other.
+///
+/// Returns the rows without a trailing newline.
+pub fn render_patterns(patterns: &[SevenSegment], style: Style) -> Vec<String> {
+    let digits: Vec<Vec<String>> = patterns
+        .iter()
+        .map(|pattern| render_pattern(pattern, style))
+        .collect();
+
+    (0..style.height())
+        .map(|row| {
+            digits
+                .iter()
+                .map(|digit| digit[row].as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Draw the scrambled output patterns of an entry next to the decoded digits.
+pub fn render_entry(output_digits: &[SevenSegment], mapping: &[u8; 7], style: Style) -> String {
+    let decoded: Vec<SevenSegment> = output_digits
+        .iter()
+        .map(|digit| digit.remap(mapping))
+        .collect();
+
+    let scrambled = render_patterns(output_digits, style);
+    let decoded = render_patterns(&decoded, style);
+    let middle = style.height() / 2;
+    let mut output = "".to_string();
+
+    for (row, (left, right)) in scrambled.iter().zip(decoded.iter()).enumerate() {
+        let separator = if row == middle { "  =>  " } else { "      " };
+        output += (left.to_string() + separator + right).trim_end();
+        output += "\n";
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        determine_mapping, parse_input_line,
+        render::{render_entry, render_patterns, Style},
+        segment::SevenSegment,
+    };
+
+    #[test]
+    fn should_render_small_digits() {
+        let patterns = [
+            SevenSegment::try_from("abcdefg").unwrap(),
+            SevenSegment::try_from("cf").unwrap(),
+        ];
+        let expected = vec![" _     ", "|_|   |", "|_|   |"];
+        let actual = render_patterns(&patterns, Style::Small);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_render_large_digits() {
+        let patterns = [SevenSegment::try_from("acdeg").unwrap()];
+        let expected = vec![
+            " aaaa ", ".    c", ".    c", " dddd ", "e    .", "e    .", " gggg ",
+        ];
+        let actual = render_patterns(&patterns, Style::Large);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_render_entry() {
+        let input =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
+                .to_string();
+        let (input_digits, output_digits) = parse_input_line(input).unwrap();
+        let mapping = determine_mapping(&input_digits).unwrap();
+        let expected = [
+            "     _       _        _   _   _   _",
+            "|_| |_| |_| |_|  =>  |_   _| |_   _|",
+            "| |   | | |   |       _|  _|  _|  _|",
+        ]
+        .map(|line| line.to_string() + "\n")
+        .concat();
+        let actual = render_entry(&output_digits, &mapping, Style::Small);
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+
+use crate::display::DisplayDefinition;
+
+/// The letters of the segments, from the highest to the lowest bit.
+const SEGMENTS: [char; 7] = ['a', 'b', 'c', 'd', 'e', 'f', 'g'];
+
+/// A character that is not a segment.
+#[derive(Debug, PartialEq)]
+pub struct InvalidSegment(pub char);
+
+/// A pattern containing characters that are not segments.
+#[derive(Debug, PartialEq)]
+pub struct InvalidPattern(pub String);
+
+/// The lit segments of a seven-segment display.
+///
+/// Segment `a` is stored in the highest of the seven bits, segment `g` in the lowest.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct SevenSegment(u8);
+
+impl SevenSegment {
+    /// Create a pattern from its bits, ignoring everything above the seven segments.
+    pub fn new(bits: u8) -> SevenSegment {
+        SevenSegment(bits & 0b1111111)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Convert the segment to a part of the pattern.
+    pub fn from_segment(segment: char) -> Result<SevenSegment, InvalidSegment> {
+        SEGMENTS
+            .iter()
+            .position(|&letter| letter == segment)
+            .map(|index| SevenSegment(1 << (6 - index)))
+            .ok_or(InvalidSegment(segment))
+    }
+
+    /// Whether the segment with the given letter is lit.
+    pub fn has_segment(&self, segment: char) -> bool {
+        SevenSegment::from_segment(segment).is_ok_and(|segment| self.0 & segment.0 != 0)
+    }
+
+    /// Count the number of activated segments.
+    pub fn segment_count(&self) -> u8 {
+        self.0.count_ones() as u8
+    }
+
+    /// Remap the segments.
+    ///
+    /// Segment `i` of the result is lit if the wire `map[i]` is lit.
+    pub fn remap(&self, map: &[u8; 7]) -> SevenSegment {
+        let mut remapped = 0u8;
+
+        for (index, segment) in map.iter().enumerate() {
+            if segment & self.0 > 0 {
+                remapped |= 1 << (6 - index);
+            }
+        }
+
+        SevenSegment(remapped)
+    }
+
+    /// Scramble the segments, the inverse of [`SevenSegment::remap`].
+    ///
+    /// Wire `map[i]` is lit if segment `i` is lit.
+    pub fn scramble(&self, map: &[u8; 7]) -> SevenSegment {
+        let mut scrambled = 0u8;
+
+        for (index, wire) in map.iter().enumerate() {
+            if self.0 & (1 << (6 - index)) != 0 {
+                scrambled |= wire;
+            }
+        }
+
+        SevenSegment::new(scrambled)
+    }
+
+    /// The decimal value of the digit, `None` if the segments don't form a digit.
+    ///
+    /// Only works after it has been converted to the default format.
+    pub fn value(&self) -> Option<u8> {
+        DisplayDefinition::seven_segment()
+            .glyph(self.0 as u32)
+            .and_then(|symbol| symbol.to_digit(10))
+            .map(|value| value as u8)
+    }
+
+    /// The pattern showing the decimal digit, `None` if it is not a single digit.
+    pub fn from_digit(value: u8) -> Option<SevenSegment> {
+        let symbol = char::from_digit(value as u32, 10)?;
+
+        DisplayDefinition::seven_segment()
+            .pattern(symbol)
+            .map(|pattern| SevenSegment::new(pattern as u8))
+    }
+}
+
+impl TryFrom<&str> for SevenSegment {
+    type Error = InvalidPattern;
+
+    /// Convert a string of segment letters to a pattern.
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        input.chars().try_fold(SevenSegment(0), |pattern, segment| {
+            let segment = SevenSegment::from_segment(segment)
+                .map_err(|_| InvalidPattern(input.to_string()))?;
+
+            Ok(SevenSegment(pattern.0 | segment.0))
+        })
+    }
+}
+
+impl fmt::Display for SevenSegment {
+    /// Print the letters of the lit segments in alphabetical order.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, segment) in SEGMENTS.iter().enumerate() {
+            if self.0 & (1 << (6 - index)) != 0 {
+                write!(f, "{}", segment)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segment::{InvalidPattern, InvalidSegment, SevenSegment};
+
+    #[test]
+    fn should_count_active_segments() {
+        let digit = SevenSegment::new(0b1011011);
+        let actual = digit.segment_count();
+
+        assert_eq!(actual, 5);
+    }
+
+    #[test]
+    fn should_parse_pattern() {
+        let expected = Ok(SevenSegment::new(0b1101011));
+        let actual = SevenSegment::try_from("abdfg");
+
+        assert_eq!(actual, expected);
+        assert_eq!(SevenSegment::try_from(""), Ok(SevenSegment::new(0)));
+    }
+
+    #[test]
+    fn should_report_invalid_pattern() {
+        let expected = Err(InvalidPattern("abxg".to_string()));
+        let actual = SevenSegment::try_from("abxg");
+
+        assert_eq!(actual, expected);
+        assert_eq!(SevenSegment::from_segment('h'), Err(InvalidSegment('h')));
+    }
+
+    #[test]
+    fn should_print_segment_letters() {
+        let pattern = SevenSegment::try_from("gfdba").unwrap();
+
+        assert_eq!(pattern.to_string(), "abdfg");
+    }
+
+    #[test]
+    fn should_remap_value() {
+        let input = SevenSegment::new(0b1101010);
+        // cgafedb -> abcdefg
+        let mapping = [
+            0b0010000, 0b0000001, 0b1000000, 0b0000010, 0b0000100, 0b0001000, 0b0100000,
+        ];
+        let expected = SevenSegment::new(0b0011011);
+
+        let actual = input.remap(&mapping);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_scramble_and_remap_digits() {
+        // cgafedb -> abcdefg
+        let mapping = [
+            0b0010000, 0b0000001, 0b1000000, 0b0000010, 0b0000100, 0b0001000, 0b0100000,
+        ];
+
+        for value in 0..10 {
+            let digit = SevenSegment::from_digit(value).unwrap();
+
+            assert_eq!(digit.scramble(&mapping).remap(&mapping), digit);
+        }
+
+        assert_eq!(SevenSegment::from_digit(10), None);
+    }
+
+    #[test]
+    fn should_convert_invalid_digit_to_none() {
+        assert_eq!(SevenSegment::new(0b1101011).value(), Some(5));
+        assert_eq!(SevenSegment::new(0b1000001).value(), None);
+    }
+}
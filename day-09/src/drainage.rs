@@ -0,0 +1,184 @@
+use crate::{rules::Connectivity, Heightmap, Point};
+
+/// The low points a cell can drain to, following any downhill path.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Outlets {
+    One(usize),
+    Many,
+}
+
+/// How water flows downhill over a heightmap.
+///
+/// Every cell drains to its steepest-descent neighbor, the lowest of its lower neighbors.
+/// Cells without a lower neighbor are sinks, which includes all low points.
+/// Flat areas are not resolved, so every cell of a flat sink is a sink on its own.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Drainage<'a> {
+    heightmap: &'a Heightmap,
+    /// The steepest-descent neighbor of every cell, `None` for sinks.
+    downstream: Vec<Option<usize>>,
+    /// The sink every cell drains to.
+    sinks: Vec<usize>,
+    /// The number of cells that flow through every cell.
+    upstream: Vec<usize>,
+    /// Whether downhill paths lead from the cell to several sinks.
+    ridges: Vec<bool>,
+}
+
+impl<'a> Drainage<'a> {
+    /// Analyze the drainage of the heightmap with the given neighbors.
+    pub fn new(heightmap: &'a Heightmap, connectivity: Connectivity) -> Drainage<'a> {
+        let cols = heightmap.cols;
+        let cell_count = heightmap.rows * heightmap.cols;
+        let index = |point: Point| point.row * cols + point.col;
+
+        let lower_neighbors = |cell: usize| {
+            let height = heightmap.heights[cell];
+
+            heightmap
+                .neighbors(cell / cols, cell % cols, connectivity)
+                .filter(move |neighbor| neighbor.height < height)
+        };
+
+        let downstream: Vec<Option<usize>> = (0..cell_count)
+            .map(|cell| {
+                // The first of the lowest neighbors on ties
+                lower_neighbors(cell)
+                    .fold(None, |lowest: Option<Point>, neighbor| match lowest {
+                        Some(lowest) if lowest.height <= neighbor.height => Some(lowest),
+                        _ => Some(neighbor),
+                    })
+                    .map(index)
+            })
+            .collect();
+
+        // Water only flows to lower cells, so going up the heights visits the sinks first
+        let mut ascending: Vec<usize> = (0..cell_count).collect();
+        ascending.sort_by_key(|&cell| heightmap.heights[cell]);
+
+        let mut sinks = vec![0; cell_count];
+        let mut outlets = vec![Outlets::Many; cell_count];
+
+        for &cell in &ascending {
+            sinks[cell] = downstream[cell].map_or(cell, |next| sinks[next]);
+
+            outlets[cell] = lower_neighbors(cell)
+                .map(|neighbor| outlets[index(neighbor)])
+                .reduce(|a, b| if a == b { a } else { Outlets::Many })
+                .unwrap_or(Outlets::One(cell));
+        }
+
+        let mut upstream = vec![0; cell_count];
+
+        for &cell in ascending.iter().rev() {
+            if let Some(next) = downstream[cell] {
+                upstream[next] += upstream[cell] + 1;
+            }
+        }
+
+        Drainage {
+            heightmap,
+            downstream,
+            sinks,
+            upstream,
+            ridges: outlets
+                .into_iter()
+                .map(|outlet| outlet == Outlets::Many)
+                .collect(),
+        }
+    }
+
+    /// The point of the cell with the given index.
+    fn point(&self, cell: usize) -> Point {
+        let cols = self.heightmap.cols;
+
+        Point::new(self.heightmap.heights[cell], cell / cols, cell % cols)
+    }
+
+    /// The steepest-descent neighbor of the cell, `None` for sinks.
+    pub fn downstream(&self, row: usize, col: usize) -> Option<Point> {
+        self.downstream[row * self.heightmap.cols + col].map(|cell| self.point(cell))
+    }
+
+    /// The sink the cell drains to, following the steepest descent.
+    pub fn sink(&self, row: usize, col: usize) -> Point {
+        self.point(self.sinks[row * self.heightmap.cols + col])
+    }
+
+    /// The number of other cells whose water flows through the cell.
+    pub fn upstream_count(&self, row: usize, col: usize) -> usize {
+        self.upstream[row * self.heightmap.cols + col]
+    }
+
+    /// Whether downhill paths lead from the cell to more than one sink.
+    pub fn is_ridge(&self, row: usize, col: usize) -> bool {
+        self.ridges[row * self.heightmap.cols + col]
+    }
+
+    /// All sinks with the number of cells draining to them, including themselves.
+    pub fn sinks(&self) -> Vec<(Point, usize)> {
+        (0..self.downstream.len())
+            .filter(|&cell| self.downstream[cell].is_none())
+            .map(|cell| (self.point(cell), self.upstream[cell] + 1))
+            .collect()
+    }
+
+    /// All cells draining to more than one sink.
+    pub fn ridges(&self) -> Vec<Point> {
+        (0..self.ridges.len())
+            .filter(|&cell| self.ridges[cell])
+            .map(|cell| self.point(cell))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{drainage::Drainage, rules::Connectivity, tests::example, Heightmap, Point};
+
+    #[test]
+    fn should_follow_steepest_descent() {
+        let heightmap = example();
+        let drainage = Drainage::new(&heightmap, Connectivity::Four);
+
+        // 8 has the lower neighbors 7 and 5
+        assert_eq!(drainage.downstream(1, 2), Some(Point::new(5, 2, 2)));
+        assert_eq!(drainage.downstream(0, 1), None);
+        assert_eq!(drainage.sink(1, 0), Point::new(1, 0, 1));
+        assert_eq!(drainage.sink(3, 4), Point::new(5, 2, 2));
+    }
+
+    #[test]
+    fn should_drain_every_cell_to_a_low_point() {
+        let heightmap = example();
+        let drainage = Drainage::new(&heightmap, Connectivity::Four);
+        let sinks = drainage.sinks();
+        let sink_points: Vec<Point> = sinks.iter().map(|&(point, _)| point).collect();
+
+        assert_eq!(sink_points, heightmap.low_points());
+        assert_eq!(sinks.iter().map(|&(_, count)| count).sum::<usize>(), 50);
+    }
+
+    #[test]
+    fn should_accumulate_upstream_cells() {
+        let heightmap = Heightmap::from_input("321\n432\n543".to_string()).unwrap();
+        let drainage = Drainage::new(&heightmap, Connectivity::Four);
+
+        assert_eq!(drainage.upstream_count(0, 2), 8);
+        assert_eq!(drainage.upstream_count(2, 0), 0);
+        // Ties go to the first neighbor, so only the 4 on the left flows through the center
+        assert_eq!(drainage.upstream_count(1, 1), 1);
+        assert!(drainage.ridges().is_empty());
+    }
+
+    #[test]
+    fn should_flag_ridges() {
+        let heightmap = example();
+        let drainage = Drainage::new(&heightmap, Connectivity::Four);
+
+        // Flows down to the 1 on the left and the 5 below
+        assert!(drainage.is_ridge(1, 1));
+        assert!(!drainage.is_ridge(1, 0));
+        assert!(!drainage.is_ridge(0, 1));
+    }
+}
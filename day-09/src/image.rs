@@ -0,0 +1,61 @@
+/// A color with red, green and blue channels.
+pub type Rgb = [u8; 3];
+
+/// Convert a color from hue, saturation and value, all in `0.0..=1.0`.
+pub fn hsv(hue: f64, saturation: f64, value: f64) -> Rgb {
+    let hue = hue.rem_euclid(1.0) * 6.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match hue as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+
+    [r, g, b].map(|channel| ((channel + m) * 255.0).round() as u8)
+}
+
+/// Encode a color image as binary PPM (`P6`).
+///
+/// The pixels are given row by row.
+pub fn encode_ppm(width: usize, height: usize, pixels: &[Rgb]) -> Vec<u8> {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Pixel count does not match the size"
+    );
+
+    let mut output = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+    for pixel in pixels {
+        output.extend_from_slice(pixel);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::{encode_ppm, hsv};
+
+    #[test]
+    fn should_convert_hsv() {
+        assert_eq!(hsv(0.0, 1.0, 1.0), [255, 0, 0]);
+        assert_eq!(hsv(1.0 / 3.0, 1.0, 1.0), [0, 255, 0]);
+        assert_eq!(hsv(2.0 / 3.0, 1.0, 0.5), [0, 0, 128]);
+        assert_eq!(hsv(0.25, 0.0, 1.0), [255, 255, 255]);
+    }
+
+    #[test]
+    fn should_encode_ppm() {
+        let expected = b"P6\n1 2\n255\n\x01\x02\x03\x04\x05\x06".to_vec();
+        let actual = encode_ppm(1, 2, &[[1, 2, 3], [4, 5, 6]]);
+
+        assert_eq!(actual, expected);
+    }
+}
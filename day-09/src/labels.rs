@@ -0,0 +1,201 @@
+use crate::{rules::BasinRules, Heightmap};
+
+/// The label of cells that belong to no basin.
+const WALL: u32 = u32::MAX;
+
+/// The label of cells that have not been reached yet.
+const UNVISITED: u32 = u32::MAX - 1;
+
+/// The basin of every cell of a heightmap.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BasinLabels {
+    cols: usize,
+    /// The basin of every cell, row by row.
+    labels: Vec<u32>,
+    /// The number of cells of every basin.
+    sizes: Vec<usize>,
+}
+
+impl BasinLabels {
+    /// The basin of the cell, `None` for walls.
+    pub fn label(&self, row: usize, col: usize) -> Option<usize> {
+        match self.labels[row * self.cols + col] {
+            WALL => None,
+            label => Some(label as usize),
+        }
+    }
+
+    /// The sizes of the basins, in the order of their labels.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    pub fn basin_count(&self) -> usize {
+        self.sizes.len()
+    }
+}
+
+impl Heightmap {
+    /// Label the basins of the whole map in a single pass.
+    pub fn label_basins(&self) -> BasinLabels {
+        self.label_basins_with(&BasinRules::default())
+    }
+
+    /// Label the basins of the whole map in a single pass, following the rules.
+    ///
+    /// Every cell is visited a constant number of times, so this runs in linear time.
+    /// Basins are labelled in the order their first cell appears, row by row.
+    pub fn label_basins_with(&self, rules: &BasinRules) -> BasinLabels {
+        let (rows, cols) = (self.rows, self.cols);
+        let mut labels = vec![UNVISITED; rows * cols];
+        let mut sizes = vec![];
+        let mut to_explore = vec![];
+
+        for start in 0..labels.len() {
+            if labels[start] != UNVISITED {
+                continue;
+            }
+            if rules.is_wall(self.heights[start]) {
+                labels[start] = WALL;
+                continue;
+            }
+
+            let label = sizes.len() as u32;
+            let mut size = 0;
+
+            labels[start] = label;
+            to_explore.push(start);
+
+            while let Some(index) = to_explore.pop() {
+                size += 1;
+
+                let (row, col) = (index / cols, index % cols);
+
+                for neighbor in self.neighbors(row, col, rules.connectivity) {
+                    let neighbor = neighbor.row * cols + neighbor.col;
+
+                    if labels[neighbor] == UNVISITED && !rules.is_wall(self.heights[neighbor]) {
+                        labels[neighbor] = label;
+                        to_explore.push(neighbor);
+                    }
+                }
+            }
+
+            sizes.push(size);
+        }
+
+        BasinLabels {
+            cols,
+            labels,
+            sizes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rules::{BasinRules, Connectivity},
+        tests::{example, flood_fill_basins},
+        Heightmap,
+    };
+
+    #[test]
+    fn should_label_basins() {
+        let labels = example().label_basins();
+
+        assert_eq!(labels.sizes(), &[3, 9, 14, 9]);
+        assert_eq!(labels.label(0, 0), Some(0));
+        assert_eq!(labels.label(0, 2), None);
+        assert_eq!(labels.label(0, 9), Some(1));
+        assert_eq!(labels.label(3, 0), Some(2));
+        assert_eq!(labels.label(4, 9), Some(3));
+    }
+
+    #[test]
+    fn should_match_flood_fill_basin_sizes() {
+        let eight = BasinRules {
+            connectivity: Connectivity::Eight,
+            ..BasinRules::default()
+        };
+        let cases = [
+            (example(), BasinRules::default(), vec![3, 9, 9, 14]),
+            (example(), eight, vec![35]),
+            // Both low points drain into the same basin
+            (
+                Heightmap::from_input("0120\n9999".to_string()).unwrap(),
+                BasinRules::default(),
+                vec![4],
+            ),
+            // A flat basin has no low point
+            (
+                Heightmap::from_input("119\n990".to_string()).unwrap(),
+                BasinRules::default(),
+                vec![1, 2],
+            ),
+        ];
+
+        for (heightmap, rules, expected) in cases {
+            let mut reference: Vec<usize> = flood_fill_basins(&heightmap, &rules)
+                .iter()
+                .map(|basin| basin.len())
+                .collect();
+            let mut actual = heightmap.label_basins_with(&rules).sizes().to_vec();
+
+            reference.sort_unstable();
+            actual.sort_unstable();
+
+            assert_eq!(reference, expected);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn should_label_basins_with_rules() {
+        let rules = BasinRules {
+            wall_height: 8,
+            connectivity: Connectivity::Eight,
+            plateaus: false,
+        };
+        let labels = example().label_basins_with(&rules);
+
+        assert_eq!(labels.sizes(), &[3, 9, 7, 6]);
+    }
+
+    #[test]
+    fn should_label_large_maps() {
+        // Walls on every fourth row and column split the map into 3x3 basins
+        let size = 1000;
+        let rows = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| if row % 4 == 3 || col % 4 == 3 { 9 } else { 1 })
+                    .collect()
+            })
+            .collect();
+        let labels = Heightmap::from_rows(rows).unwrap().label_basins();
+
+        assert_eq!(labels.basin_count(), 250 * 250);
+        assert!(labels.sizes().iter().all(|&size| size == 9));
+    }
+
+    #[test]
+    fn should_label_single_spiral_basin() {
+        // A long winding basin, explored without recursion
+        let size = 301;
+        let rows = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| match (row % 4, col) {
+                        (1, col) if col < size - 1 => 9,
+                        (3, col) if col > 0 => 9,
+                        _ => 0,
+                    })
+                    .collect()
+            })
+            .collect();
+        let labels = Heightmap::from_rows(rows).unwrap().label_basins();
+
+        assert_eq!(labels.basin_count(), 1);
+    }
+}
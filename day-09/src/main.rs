@@ -1,4 +1,16 @@
-use std::fs;
+use std::{env, fs};
+
+use drainage::Drainage;
+use query::BasinIndex;
+use render::BasinMap;
+use rules::{BasinRules, Connectivity};
+
+mod drainage;
+mod image;
+mod labels;
+mod query;
+mod render;
+mod rules;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 struct Point {
@@ -13,36 +25,98 @@ impl Point {
     }
 }
 
+/// A heightmap that could not be parsed.
+#[derive(Debug, PartialEq)]
+enum ParseError {
+    /// The row does not have as many columns as the first row.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// The character at the position is not a digit.
+    InvalidHeight {
+        row: usize,
+        col: usize,
+        character: char,
+    },
+}
+
 #[derive(Debug, PartialEq)]
-struct Heightmap<const R: usize, const C: usize> {
-    heights: [[u8; C]; R],
+struct Heightmap {
+    rows: usize,
+    cols: usize,
+    /// The heights, row by row.
+    heights: Vec<u8>,
 }
 
-impl<const R: usize, const C: usize> Heightmap<R, C> {
-    fn parse_row(input: String) -> [u8; C] {
-        let mut row = [0u8; C];
+impl Heightmap {
+    fn parse_row(row: usize, input: &str) -> Result<Vec<u8>, ParseError> {
+        input
+            .chars()
+            .enumerate()
+            .map(|(col, character)| {
+                character
+                    .to_digit(10)
+                    .map(|height| height as u8)
+                    .ok_or(ParseError::InvalidHeight {
+                        row,
+                        col,
+                        character,
+                    })
+            })
+            .collect()
+    }
+
+    fn from_input(input: String) -> Result<Self, ParseError> {
+        let rows = input
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(row, line)| Self::parse_row(row, line.trim_end()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_rows(rows)
+    }
 
-        for (index, ch) in input.char_indices() {
-            row[index] = ch.to_string().parse().unwrap();
+    /// Create a heightmap from its rows, which must all have the same length.
+    fn from_rows(rows: Vec<Vec<u8>>) -> Result<Self, ParseError> {
+        let cols = rows.first().map_or(0, |row| row.len());
+
+        for (row, heights) in rows.iter().enumerate() {
+            if heights.len() != cols {
+                return Err(ParseError::RaggedRow {
+                    row,
+                    expected: cols,
+                    actual: heights.len(),
+                });
+            }
         }
 
-        row
+        Ok(Heightmap {
+            rows: rows.len(),
+            cols,
+            heights: rows.concat(),
+        })
     }
 
-    fn from_input(input: String) -> Self {
-        let mut heights = [[0u8; C]; R];
+    /// The height at the position, which must be within the heightmap.
+    fn height(&self, row: usize, col: usize) -> u8 {
+        self.heights[row * self.cols + col]
+    }
 
-        for (col, line) in input.trim().split("\n").enumerate() {
-            heights[col] = Self::parse_row(line.to_string());
-        }
+    /// Parse a cell position like `2,7`, `None` if it is not within the heightmap.
+    fn parse_cell(&self, input: &str) -> Option<(usize, usize)> {
+        let (row, col) = input.split_once(',')?;
+        let (row, col) = (row.trim().parse().ok()?, col.trim().parse().ok()?);
 
-        Heightmap { heights }
+        (row < self.rows && col < self.cols).then_some((row, col))
     }
 
     fn point(&self, row: i32, col: i32) -> Option<Point> {
-        if row >= 0 && row < R as i32 && col >= 0 && col < C as i32 {
+        if row >= 0 && row < self.rows as i32 && col >= 0 && col < self.cols as i32 {
             Some(Point::new(
-                self.heights[row as usize][col as usize],
+                self.height(row as usize, col as usize),
                 row as usize,
                 col as usize,
             ))
@@ -52,32 +126,7 @@ impl<const R: usize, const C: usize> Heightmap<R, C> {
     }
 
     fn low_points(&self) -> Vec<Point> {
-        let mut low_points = vec![];
-
-        for row in 0..R {
-            for col in 0..C {
-                let height = self.heights[row][col];
-                let mut is_low_point = true;
-
-                // Check if the adjacent values are all higher
-                for (dr, dc) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-                    let (r_index, c_index) = (row as i32 + dr, col as i32 + dc);
-
-                    if r_index >= 0 && r_index < R as i32 && c_index >= 0 && c_index < C as i32 {
-                        if self.heights[r_index as usize][c_index as usize] <= height {
-                            is_low_point = false;
-                            break;
-                        }
-                    }
-                }
-
-                if is_low_point {
-                    low_points.push(Point::new(height, row, col));
-                }
-            }
-        }
-
-        low_points
+        self.low_points_with(&BasinRules::default())
     }
 
     fn low_point_risk_value(&self) -> u32 {
@@ -87,49 +136,8 @@ impl<const R: usize, const C: usize> Heightmap<R, C> {
             .sum()
     }
 
-    fn basins(&self) -> Vec<Vec<Point>> {
-        let low_points = self.low_points();
-
-        low_points
-            .into_iter()
-            .map(|low_point| {
-                let mut basin_points: Vec<Point> = vec![];
-                let mut visited_points = vec![low_point.clone()];
-                let mut points_to_explore = vec![low_point.clone()];
-
-                while points_to_explore.len() > 0 {
-                    let mut new_points_to_explore: Vec<Point> = vec![];
-
-                    for exp_point in &points_to_explore {
-                        basin_points.push(exp_point.clone());
-
-                        // Investigate adjacent points
-                        for (dr, dc) in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
-                            if let Some(point) =
-                                self.point(exp_point.row as i32 + dr, exp_point.col as i32 + dc)
-                            {
-                                if !visited_points.contains(&point) && point.height != 9 {
-                                    visited_points.push(point);
-                                    new_points_to_explore.push(point);
-                                }
-                            }
-                        }
-                    }
-
-                    points_to_explore = new_points_to_explore;
-                }
-
-                basin_points
-            })
-            .collect()
-    }
-
-    fn basin_sizes(&self) -> Vec<usize> {
-        self.basins().into_iter().map(|basin| basin.len()).collect()
-    }
-
     fn biggest_basin_sizes(&self) -> usize {
-        let mut sizes = self.basin_sizes();
+        let mut sizes = self.label_basins().sizes().to_vec();
         sizes.sort();
         sizes.into_iter().rev().take(3).product()
     }
@@ -140,7 +148,8 @@ fn main() {
     let filename = "./input/input.txt";
     let input = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
-    let heightmap = Heightmap::<100, 100>::from_input(input);
+    let heightmap =
+        Heightmap::from_input(input).expect("Something went wrong parsing the heightmap");
     let low_point_risk_value = heightmap.low_point_risk_value();
 
     println!("Low point risk value: {}", low_point_risk_value);
@@ -148,17 +157,125 @@ fn main() {
     let biggest_basin_sizes = heightmap.biggest_basin_sizes();
 
     println!("Biggest basin sizes: {}", biggest_basin_sizes);
+
+    // Optionally draw the basins in the terminal and export them as image
+    if let Some(path) = env::args().nth(1) {
+        let basin_map = BasinMap::new(&heightmap);
+
+        print!("{}", basin_map.to_ansi());
+        fs::write(&path, basin_map.to_ppm(4)).expect("Something went wrong writing the image");
+        println!("Basin map written to {}", path);
+    }
+
+    // Optionally describe a cell, `row,col`, with `eight` neighbors as last argument
+    if let Some(cell) = env::args().nth(2) {
+        let (row, col) = heightmap
+            .parse_cell(&cell)
+            .expect("Something went wrong parsing the cell");
+        let connectivity = match env::args().nth(3).as_deref() {
+            Some("eight") => Connectivity::Eight,
+            _ => Connectivity::Four,
+        };
+        let rules = BasinRules {
+            connectivity,
+            ..BasinRules::default()
+        };
+        let index = BasinIndex::new(&heightmap, &rules);
+        let drainage = Drainage::new(&heightmap, connectivity);
+
+        println!("Number of basins: {}", heightmap.basins_with(&rules).len());
+        println!(
+            "Cell {},{}: risk level {}, basin {:?} of size {:?}",
+            row,
+            col,
+            index.risk_level(row, col),
+            index.basin(row, col),
+            index.basin_size(row, col)
+        );
+        println!(
+            "Low point of the basin: {:?}, {:?} steps away",
+            index.low_point(row, col),
+            index.distance(row, col)
+        );
+        println!(
+            "Drains to {:?} via {:?}, {} cells flow through, ridge: {}",
+            drainage.sink(row, col),
+            drainage.downstream(row, col),
+            drainage.upstream_count(row, col),
+            drainage.is_ridge(row, col)
+        );
+        println!(
+            "Sinks: {}, ridge cells: {}",
+            drainage.sinks().len(),
+            drainage.ridges().len()
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Heightmap, Point};
+    use std::collections::HashSet;
+
+    use crate::{rules::BasinRules, Heightmap, ParseError, Point};
+
+    /// The example heightmap of the puzzle, shared by the tests of all modules.
+    pub(crate) fn example() -> Heightmap {
+        let input = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678".to_string();
+
+        Heightmap::from_input(input).unwrap()
+    }
+
+    /// The basins found with one flood fill each, as reference for the labelling.
+    ///
+    /// Explores from the low points first and then from the cells not reached yet,
+    /// so basins without low point are found as well and shared basins only once.
+    pub(crate) fn flood_fill_basins(heightmap: &Heightmap, rules: &BasinRules) -> Vec<Vec<Point>> {
+        let mut basins = vec![];
+        let mut visited_points: HashSet<(usize, usize)> = HashSet::new();
+        let cells = (0..heightmap.rows).flat_map(|row| {
+            (0..heightmap.cols).map(move |col| Point::new(heightmap.height(row, col), row, col))
+        });
+
+        for start in heightmap.low_points_with(rules).into_iter().chain(cells) {
+            if rules.is_wall(start.height) || !visited_points.insert((start.row, start.col)) {
+                continue;
+            }
+
+            let mut basin_points: Vec<Point> = vec![];
+            let mut points_to_explore = vec![start];
+
+            while !points_to_explore.is_empty() {
+                let mut new_points_to_explore: Vec<Point> = vec![];
+
+                for exp_point in &points_to_explore {
+                    basin_points.push(*exp_point);
+
+                    // Investigate adjacent points
+                    for point in
+                        heightmap.neighbors(exp_point.row, exp_point.col, rules.connectivity)
+                    {
+                        if !rules.is_wall(point.height)
+                            && visited_points.insert((point.row, point.col))
+                        {
+                            new_points_to_explore.push(point);
+                        }
+                    }
+                }
+
+                points_to_explore = new_points_to_explore;
+            }
+
+            basins.push(basin_points);
+        }
+
+        basins
+    }
 
     #[test]
     fn should_parse_row() {
-        let input = "2199943210".to_string();
-        let expected = [2, 1, 9, 9, 9, 4, 3, 2, 1, 0];
-        let actual = Heightmap::<1, 10>::parse_row(input);
+        let input = "2199943210";
+        let expected = Ok(vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0]);
+        let actual = Heightmap::parse_row(0, input);
 
         assert_eq!(actual, expected);
     }
@@ -166,31 +283,68 @@ mod tests {
     #[test]
     fn should_parse_heightmap() {
         let input = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678".to_string();
-        let expected = Heightmap {
-            heights: [
-                [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-                [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-                [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-                [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-                [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-            ],
-        };
-        let actual = Heightmap::<5, 10>::from_input(input);
+        let expected = Heightmap::from_rows(vec![
+            vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
+            vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
+            vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
+            vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
+            vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
+        ]);
+        let actual = Heightmap::from_input(input);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_parse_cell() {
+        let heightmap = example();
+
+        assert_eq!(heightmap.parse_cell("2,7"), Some((2, 7)));
+        assert_eq!(heightmap.parse_cell("4, 9"), Some((4, 9)));
+        assert_eq!(heightmap.parse_cell("5,0"), None);
+        assert_eq!(heightmap.parse_cell("0,10"), None);
+        assert_eq!(heightmap.parse_cell("-1,0"), None);
+        assert_eq!(heightmap.parse_cell("1"), None);
+    }
+
+    #[test]
+    fn should_parse_heightmap_of_any_size() {
+        let heightmap = Heightmap::from_input("123\n456\n".to_string()).unwrap();
+
+        assert_eq!((heightmap.rows, heightmap.cols), (2, 3));
+        assert_eq!(heightmap.height(1, 0), 4);
+        assert_eq!(heightmap.low_points(), vec![Point::new(1, 0, 0)]);
+    }
+
+    #[test]
+    fn should_report_ragged_rows() {
+        let input = "2199943210\n398789492\n9856789892".to_string();
+        let expected = Err(ParseError::RaggedRow {
+            row: 1,
+            expected: 10,
+            actual: 9,
+        });
+        let actual = Heightmap::from_input(input);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_report_invalid_heights() {
+        let input = "2199943210\n3987894921\n98567x9892".to_string();
+        let expected = Err(ParseError::InvalidHeight {
+            row: 2,
+            col: 5,
+            character: 'x',
+        });
+        let actual = Heightmap::from_input(input);
 
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn should_determine_low_points() {
-        let heightmap = Heightmap {
-            heights: [
-                [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-                [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-                [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-                [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-                [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-            ],
-        };
+        let heightmap = example();
         let expected = vec![
             Point::new(1, 0, 1),
             Point::new(0, 0, 9),
@@ -204,15 +358,7 @@ mod tests {
 
     #[test]
     fn should_determine_low_point_risk_value() {
-        let heightmap = Heightmap {
-            heights: [
-                [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-                [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-                [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-                [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-                [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-            ],
-        };
+        let heightmap = example();
         let expected = 15;
         let actual = heightmap.low_point_risk_value();
 
@@ -221,33 +367,27 @@ mod tests {
 
     #[test]
     fn should_determine_basin_sizes() {
-        let heightmap = Heightmap {
-            heights: [
-                [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-                [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-                [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-                [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-                [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-            ],
-        };
+        let heightmap = example();
+        let rules = BasinRules::default();
         let expected = vec![3, 9, 14, 9];
 
-        let actual = heightmap.basin_sizes();
+        let actual: Vec<usize> = heightmap
+            .basins_with(&rules)
+            .iter()
+            .map(|basin| basin.len())
+            .collect();
+        let reference: Vec<usize> = flood_fill_basins(&heightmap, &rules)
+            .iter()
+            .map(|basin| basin.len())
+            .collect();
 
         assert_eq!(actual, expected);
+        assert_eq!(reference, expected);
     }
 
     #[test]
     fn should_determine_biggest_basin_sizes() {
-        let heightmap = Heightmap {
-            heights: [
-                [2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
-                [3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
-                [9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
-                [8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
-                [9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-            ],
-        };
+        let heightmap = example();
         let expected = 1134;
 
         let actual = heightmap.biggest_basin_sizes();
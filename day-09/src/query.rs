@@ -0,0 +1,184 @@
+use std::collections::VecDeque;
+
+use crate::{
+    labels::BasinLabels,
+    rules::{BasinRules, Connectivity},
+    Heightmap, Point,
+};
+
+/// The distance of cells that cannot reach the low point of their basin.
+const UNREACHABLE: u32 = u32::MAX;
+
+/// The basins of a heightmap, indexed to answer questions about a cell in constant time.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BasinIndex<'a> {
+    heightmap: &'a Heightmap,
+    labels: BasinLabels,
+    /// The low point of every basin, in the order of their labels.
+    low_points: Vec<Option<Point>>,
+    /// The number of steps from every cell to the low point of its basin, row by row.
+    distances: Vec<u32>,
+}
+
+impl<'a> BasinIndex<'a> {
+    /// Index the basins, following the rules.
+    ///
+    /// A basin containing several low points uses the lowest one, the first one on ties.
+    pub fn new(heightmap: &'a Heightmap, rules: &BasinRules) -> BasinIndex<'a> {
+        let labels = heightmap.label_basins_with(rules);
+        let mut low_points: Vec<Option<Point>> = vec![None; labels.basin_count()];
+
+        for point in heightmap.low_points_with(rules) {
+            if let Some(label) = labels.label(point.row, point.col) {
+                let low_point = &mut low_points[label];
+
+                if low_point.is_none_or(|low_point| point.height < low_point.height) {
+                    *low_point = Some(point);
+                }
+            }
+        }
+
+        let mut distances = vec![UNREACHABLE; heightmap.rows * heightmap.cols];
+
+        // Every basin is explored from its own low point, so every cell is visited once
+        for low_point in low_points.iter().flatten() {
+            Self::measure_distances(
+                heightmap,
+                &labels,
+                *low_point,
+                rules.connectivity,
+                &mut distances,
+            );
+        }
+
+        BasinIndex {
+            heightmap,
+            labels,
+            low_points,
+            distances,
+        }
+    }
+
+    /// Measure the distance of every cell of the basin to its low point.
+    fn measure_distances(
+        heightmap: &Heightmap,
+        labels: &BasinLabels,
+        low_point: Point,
+        connectivity: Connectivity,
+        distances: &mut [u32],
+    ) {
+        let cols = heightmap.cols;
+        let label = labels.label(low_point.row, low_point.col);
+        let mut to_explore = VecDeque::from([low_point]);
+
+        distances[low_point.row * cols + low_point.col] = 0;
+
+        while let Some(point) = to_explore.pop_front() {
+            let distance = distances[point.row * cols + point.col];
+
+            for neighbor in heightmap.neighbors(point.row, point.col, connectivity) {
+                let index = neighbor.row * cols + neighbor.col;
+
+                if distances[index] == UNREACHABLE
+                    && labels.label(neighbor.row, neighbor.col) == label
+                {
+                    distances[index] = distance + 1;
+                    to_explore.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    /// The basin of the cell, `None` for walls.
+    pub fn basin(&self, row: usize, col: usize) -> Option<usize> {
+        self.labels.label(row, col)
+    }
+
+    /// The number of cells of the basin containing the cell, `None` for walls.
+    pub fn basin_size(&self, row: usize, col: usize) -> Option<usize> {
+        self.basin(row, col).map(|label| self.labels.sizes()[label])
+    }
+
+    /// The low point of the basin containing the cell.
+    ///
+    /// `None` for walls and basins without low point, like a flat basin without plateaus.
+    pub fn low_point(&self, row: usize, col: usize) -> Option<Point> {
+        self.basin(row, col)
+            .and_then(|label| self.low_points[label])
+    }
+
+    /// The risk level of the cell, one more than its height.
+    pub fn risk_level(&self, row: usize, col: usize) -> u32 {
+        self.heightmap.height(row, col) as u32 + 1
+    }
+
+    /// The number of steps within the basin from the cell to its low point.
+    pub fn distance(&self, row: usize, col: usize) -> Option<u32> {
+        match self.distances[row * self.heightmap.cols + col] {
+            UNREACHABLE => None,
+            distance => Some(distance),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{query::BasinIndex, rules::BasinRules, tests::example, Heightmap, Point};
+
+    #[test]
+    fn should_query_cells() {
+        let heightmap = example();
+        let index = BasinIndex::new(&heightmap, &BasinRules::default());
+
+        assert_eq!(index.basin(4, 9), Some(3));
+        assert_eq!(index.basin_size(4, 9), Some(9));
+        assert_eq!(index.low_point(4, 9), Some(Point::new(5, 4, 6)));
+        assert_eq!(index.risk_level(4, 9), 9);
+        assert_eq!(index.distance(4, 9), Some(3));
+        assert_eq!(index.distance(4, 6), Some(0));
+    }
+
+    #[test]
+    fn should_query_walls() {
+        let heightmap = example();
+        let index = BasinIndex::new(&heightmap, &BasinRules::default());
+
+        assert_eq!(index.basin(0, 2), None);
+        assert_eq!(index.basin_size(0, 2), None);
+        assert_eq!(index.low_point(0, 2), None);
+        assert_eq!(index.risk_level(0, 2), 10);
+        assert_eq!(index.distance(0, 2), None);
+    }
+
+    #[test]
+    fn should_match_low_points() {
+        let heightmap = example();
+        let index = BasinIndex::new(&heightmap, &BasinRules::default());
+
+        for point in heightmap.low_points() {
+            assert_eq!(index.low_point(point.row, point.col), Some(point));
+            assert_eq!(index.distance(point.row, point.col), Some(0));
+        }
+    }
+
+    #[test]
+    fn should_use_lowest_low_point_of_basin() {
+        // The cells in columns 1 and 4 are both low points of the same basin
+        let heightmap = Heightmap::from_input("21232".to_string()).unwrap();
+        let index = BasinIndex::new(&heightmap, &BasinRules::default());
+
+        assert_eq!(heightmap.low_points().len(), 2);
+        assert_eq!(index.low_point(0, 4), Some(Point::new(1, 0, 1)));
+        assert_eq!(index.distance(0, 4), Some(3));
+    }
+
+    #[test]
+    fn should_handle_basins_without_low_point() {
+        let heightmap = Heightmap::from_input("55\n99".to_string()).unwrap();
+        let index = BasinIndex::new(&heightmap, &BasinRules::default());
+
+        assert_eq!(index.basin(0, 0), Some(0));
+        assert_eq!(index.low_point(0, 0), None);
+        assert_eq!(index.distance(0, 0), None);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    image::{encode_ppm, hsv, Rgb},
+    labels::BasinLabels,
+    Heightmap,
+};
+
+/// The color of the walls between the basins.
+const WALL_COLOR: Rgb = [48, 48, 48];
+
+/// The color of the low points.
+const LOW_POINT_COLOR: Rgb = [255, 255, 255];
+
+/// The number of basins that are highlighted.
+const HIGHLIGHTED_BASINS: usize = 3;
+
+/// A heightmap with each basin in its own color.
+///
+/// The largest basins are drawn in bright colors, the others in muted ones.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BasinMap<'a> {
+    heightmap: &'a Heightmap,
+    labels: BasinLabels,
+    /// The labels of the largest basins.
+    highlighted: Vec<usize>,
+    /// Whether every cell is a low point, row by row.
+    low_points: Vec<bool>,
+}
+
+impl<'a> BasinMap<'a> {
+    pub fn new(heightmap: &'a Heightmap) -> BasinMap<'a> {
+        let labels = heightmap.label_basins();
+
+        // The largest basins, the lowest label first on ties
+        let mut by_size: Vec<usize> = (0..labels.basin_count()).collect();
+        by_size.sort_by_key(|&label| std::cmp::Reverse(labels.sizes()[label]));
+        by_size.truncate(HIGHLIGHTED_BASINS);
+
+        let mut low_points = vec![false; heightmap.rows * heightmap.cols];
+
+        for point in heightmap.low_points() {
+            low_points[point.row * heightmap.cols + point.col] = true;
+        }
+
+        BasinMap {
+            heightmap,
+            labels,
+            highlighted: by_size,
+            low_points,
+        }
+    }
+
+    /// The color of the basin with the given label.
+    fn basin_color(&self, label: usize) -> Rgb {
+        // Rotating by the golden ratio keeps neighboring labels apart
+        let hue = label as f64 * 0.618_033_988_75;
+
+        if self.highlighted.contains(&label) {
+            hsv(hue, 0.9, 1.0)
+        } else {
+            hsv(hue, 0.35, 0.6)
+        }
+    }
+
+    /// The color of the cell.
+    pub fn color(&self, row: usize, col: usize) -> Rgb {
+        if self.low_points[row * self.heightmap.cols + col] {
+            return LOW_POINT_COLOR;
+        }
+
+        match self.labels.label(row, col) {
+            Some(label) => self.basin_color(label),
+            None => WALL_COLOR,
+        }
+    }
+
+    /// Draw the heights with ANSI escape codes for terminals with true color.
+    ///
+    /// Low points are marked with `*` and walls are dimmed.
+    pub fn to_ansi(&self) -> String {
+        let mut output = "".to_string();
+
+        for row in 0..self.heightmap.rows {
+            for col in 0..self.heightmap.cols {
+                let [r, g, b] = self.color(row, col);
+                let height = self.heightmap.height(row, col);
+
+                let symbol = if self.low_points[row * self.heightmap.cols + col] {
+                    "\x1b[1m*".to_string()
+                } else if self.labels.label(row, col).is_none() {
+                    format!("\x1b[2m{}", height)
+                } else {
+                    height.to_string()
+                };
+
+                output += &format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, symbol);
+            }
+
+            output += "\n";
+        }
+
+        output
+    }
+
+    /// Draw the map as PPM image, with every cell as a square of the given size.
+    pub fn to_ppm(&self, cell_size: usize) -> Vec<u8> {
+        let (width, height) = (
+            self.heightmap.cols * cell_size,
+            self.heightmap.rows * cell_size,
+        );
+        let pixels: Vec<Rgb> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (y / cell_size, x / cell_size)))
+            .map(|(row, col)| self.color(row, col))
+            .collect();
+
+        encode_ppm(width, height, &pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        render::{BasinMap, LOW_POINT_COLOR, WALL_COLOR},
+        tests::example,
+        Heightmap,
+    };
+
+    #[test]
+    fn should_highlight_largest_basins() {
+        let heightmap = example();
+        let map = BasinMap::new(&heightmap);
+        let sizes = heightmap.label_basins().sizes().to_vec();
+        let product: usize = map.highlighted.iter().map(|&label| sizes[label]).product();
+
+        assert_eq!(map.highlighted, vec![2, 1, 3]);
+        assert_eq!(product, heightmap.biggest_basin_sizes());
+    }
+
+    #[test]
+    fn should_color_cells() {
+        let heightmap = example();
+        let map = BasinMap::new(&heightmap);
+
+        assert_eq!(map.color(0, 1), LOW_POINT_COLOR);
+        assert_eq!(map.color(0, 2), WALL_COLOR);
+        // The small basin is muted, the others are bright and differ from each other
+        assert!(map.color(0, 0).iter().all(|&channel| channel <= 153));
+        assert_ne!(map.color(0, 8), map.color(3, 0));
+    }
+
+    #[test]
+    fn should_render_ansi() {
+        let heightmap = Heightmap::from_input("19\n91".to_string()).unwrap();
+        let map = BasinMap::new(&heightmap);
+        let ansi = map.to_ansi();
+        let lines: Vec<&str> = ansi.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "\x1b[38;2;255;255;255m\x1b[1m*\x1b[0m\x1b[38;2;48;48;48m\x1b[2m9\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn should_render_ppm() {
+        let heightmap = example();
+        let image = BasinMap::new(&heightmap).to_ppm(2);
+        let header = b"P6\n20 10\n255\n";
+
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 20 * 10 * 3);
+        // The top left pixel belongs to the first cell
+        assert_eq!(
+            image[header.len()..header.len() + 3],
+            BasinMap::new(&heightmap).color(0, 0)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{Heightmap, Point};
+
+/// The neighbors of a cell.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Connectivity {
+    /// The cells above, below, left and right.
+    Four,
+    /// The cells around, including the diagonal ones.
+    Eight,
+}
+
+impl Connectivity {
+    /// The row and column offsets of the neighbors.
+    pub fn offsets(&self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &[(1, 0), (0, 1), (-1, 0), (0, -1)],
+            Connectivity::Eight => &[
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ],
+        }
+    }
+}
+
+/// The rules for finding low points and basins.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BasinRules {
+    /// Cells at least this high are walls, which belong to no basin.
+    pub wall_height: u8,
+    pub connectivity: Connectivity,
+    /// Whether a flat region lower than all its neighbors counts as one low region.
+    pub plateaus: bool,
+}
+
+impl Default for BasinRules {
+    /// The rules of the puzzle: walls of height 9, four neighbors and no plateaus.
+    fn default() -> Self {
+        BasinRules {
+            wall_height: 9,
+            connectivity: Connectivity::Four,
+            plateaus: false,
+        }
+    }
+}
+
+impl BasinRules {
+    pub fn is_wall(&self, height: u8) -> bool {
+        height >= self.wall_height
+    }
+}
+
+impl Heightmap {
+    /// The neighbors of the cell within the heightmap.
+    pub fn neighbors(
+        &self,
+        row: usize,
+        col: usize,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Point> + '_ {
+        connectivity
+            .offsets()
+            .iter()
+            .filter_map(move |&(dr, dc)| self.point(row as i32 + dr, col as i32 + dc))
+    }
+
+    /// The regions that are lower than all their neighbors.
+    ///
+    /// Without plateaus, these are single cells lower than all their neighbors.
+    /// With plateaus, a region is a connected area of the same height.
+    /// Walls are never low regions.
+    pub fn low_regions(&self, rules: &BasinRules) -> Vec<Vec<Point>> {
+        let mut regions = vec![];
+        let mut visited = vec![false; self.rows * self.cols];
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let height = self.height(row, col);
+
+                if visited[row * self.cols + col] || rules.is_wall(height) {
+                    continue;
+                }
+
+                // The connected cells of the same height, if plateaus are enabled
+                let mut region = vec![Point::new(height, row, col)];
+                let mut is_low_region = true;
+                let mut index = 0;
+                visited[row * self.cols + col] = true;
+
+                while index < region.len() {
+                    let point = region[index];
+                    index += 1;
+
+                    for neighbor in self.neighbors(point.row, point.col, rules.connectivity) {
+                        if neighbor.height < height
+                            || (neighbor.height == height && !rules.plateaus)
+                        {
+                            is_low_region = false;
+                        } else if neighbor.height == height
+                            && !visited[neighbor.row * self.cols + neighbor.col]
+                        {
+                            visited[neighbor.row * self.cols + neighbor.col] = true;
+                            region.push(neighbor);
+                        }
+                    }
+                }
+
+                if is_low_region {
+                    regions.push(region);
+                }
+            }
+        }
+
+        regions
+    }
+
+    /// The low points, one for every low region.
+    pub fn low_points_with(&self, rules: &BasinRules) -> Vec<Point> {
+        self.low_regions(rules)
+            .into_iter()
+            .map(|region| region[0])
+            .collect()
+    }
+
+    /// The basins, in the order of their labels, with their points in row order.
+    ///
+    /// Like the labelling, every connected area without walls is one basin. A basin holding
+    /// several low regions, like when walls have diagonal gaps, is only returned once, and
+    /// a basin without low region, like a flat one without plateaus, is returned as well.
+    pub fn basins_with(&self, rules: &BasinRules) -> Vec<Vec<Point>> {
+        let labels = self.label_basins_with(rules);
+        let mut basins: Vec<Vec<Point>> = vec![vec![]; labels.basin_count()];
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                if let Some(label) = labels.label(row, col) {
+                    basins[label].push(Point::new(self.height(row, col), row, col));
+                }
+            }
+        }
+
+        basins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rules::{BasinRules, Connectivity},
+        tests::{example, flood_fill_basins},
+        Heightmap, Point,
+    };
+
+    /// The cells of every basin, sorted to compare basins found in any order.
+    fn sorted_cells(basins: Vec<Vec<Point>>) -> Vec<Vec<(usize, usize)>> {
+        let mut basins: Vec<Vec<(usize, usize)>> = basins
+            .iter()
+            .map(|basin| {
+                let mut cells: Vec<(usize, usize)> =
+                    basin.iter().map(|point| (point.row, point.col)).collect();
+                cells.sort_unstable();
+                cells
+            })
+            .collect();
+        basins.sort_unstable();
+        basins
+    }
+
+    /// The sorted basin sizes, after checking the basins against the flood fill.
+    fn sorted_sizes(heightmap: &Heightmap, rules: &BasinRules) -> Vec<usize> {
+        let basins = sorted_cells(heightmap.basins_with(rules));
+
+        assert_eq!(basins, sorted_cells(flood_fill_basins(heightmap, rules)));
+
+        let mut sizes: Vec<usize> = basins.iter().map(|basin| basin.len()).collect();
+        sizes.sort_unstable();
+        sizes
+    }
+
+    #[test]
+    fn should_use_puzzle_rules_by_default() {
+        let heightmap = example();
+        let rules = BasinRules::default();
+
+        assert_eq!(heightmap.low_points_with(&rules), heightmap.low_points());
+        assert_eq!(sorted_sizes(&heightmap, &rules), vec![3, 9, 9, 14]);
+    }
+
+    #[test]
+    fn should_return_shared_basin_once() {
+        // Both low points drain into the same basin
+        let heightmap = Heightmap::from_input("0120\n9999".to_string()).unwrap();
+        let rules = BasinRules::default();
+
+        assert_eq!(heightmap.low_points_with(&rules).len(), 2);
+        assert_eq!(sorted_sizes(&heightmap, &rules), vec![4]);
+    }
+
+    #[test]
+    fn should_return_basins_without_low_point() {
+        // The flat basin in the top left has no low point without plateaus
+        let heightmap = Heightmap::from_input("119\n990".to_string()).unwrap();
+        let rules = BasinRules::default();
+
+        assert_eq!(heightmap.low_points_with(&rules), vec![Point::new(0, 1, 2)]);
+        assert_eq!(sorted_sizes(&heightmap, &rules), vec![1, 2]);
+        assert_eq!(heightmap.biggest_basin_sizes(), 2);
+    }
+
+    #[test]
+    fn should_use_wall_height() {
+        let rules = BasinRules {
+            wall_height: 8,
+            ..BasinRules::default()
+        };
+
+        assert_eq!(sorted_sizes(&example(), &rules), vec![3, 6, 7, 9]);
+    }
+
+    #[test]
+    fn should_use_eight_neighbors() {
+        let rules = BasinRules {
+            connectivity: Connectivity::Eight,
+            ..BasinRules::default()
+        };
+        let heightmap = example();
+
+        // Diagonal gaps in the walls join all basins into one
+        assert_eq!(heightmap.low_points_with(&rules), heightmap.low_points());
+        assert_eq!(sorted_sizes(&heightmap, &rules), vec![35]);
+
+        // The top left cell has a lower diagonal neighbor
+        let heightmap = Heightmap::from_input("12\n20".to_string()).unwrap();
+
+        assert_eq!(heightmap.low_points().len(), 2);
+        assert_eq!(heightmap.low_points_with(&rules), vec![Point::new(0, 1, 1)]);
+    }
+
+    #[test]
+    fn should_find_low_plateaus() {
+        let input = "9999\n9119\n9229\n3999".to_string();
+        let heightmap = Heightmap::from_input(input).unwrap();
+        let rules = BasinRules {
+            plateaus: true,
+            ..BasinRules::default()
+        };
+
+        assert_eq!(heightmap.low_points(), vec![Point::new(3, 3, 0)]);
+        assert_eq!(
+            heightmap.low_regions(&rules),
+            vec![
+                vec![Point::new(1, 1, 1), Point::new(1, 1, 2)],
+                vec![Point::new(3, 3, 0)]
+            ]
+        );
+        assert_eq!(sorted_sizes(&heightmap, &rules), vec![1, 4]);
+    }
+}